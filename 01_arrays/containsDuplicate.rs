@@ -1,5 +1,11 @@
+#[allow(dead_code)]
+#[path = "../02_hashTables/hashSet.rs"]
+mod hash_set;
+
+use hash_set::HashSet;
+
 fn contains_duplicate(nums: Vec<i32>) -> bool {
-    let mut obj_nums = std::collections::HashSet::new();
+    let mut obj_nums = HashSet::new();
 
     for int_num in nums {
         if obj_nums.contains(&int_num) {
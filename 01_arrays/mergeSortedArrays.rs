@@ -1,3 +1,6 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
 fn merge_sorted_arrays(array1: &[i32], array2: &[i32]) -> Vec<i32> {
     let mut merged_array = Vec::new();
     let mut i = 0;
@@ -24,8 +27,36 @@ fn merge_sorted_arrays(array1: &[i32], array2: &[i32]) -> Vec<i32> {
     merged_array
 }
 
+/// Merges any number of sorted arrays into one sorted array.
+///
+/// A min-heap holds the next unmerged element of every array, so each step takes the smallest
+/// of the k heads in O(log k) time, and merging n elements in total takes O(n log k) time.
+/// Equal elements come out in the order of the arrays they came from.
+pub fn merge_k_sorted_arrays<T: Ord + Clone>(arrays: &[&[T]]) -> Vec<T> {
+    let mut heap = BinaryHeap::new();
+    for (index, array) in arrays.iter().enumerate() {
+        if let Some(first) = array.first() {
+            heap.push(Reverse((first, index, 0)));
+        }
+    }
+
+    let mut merged_array = Vec::with_capacity(arrays.iter().map(|array| array.len()).sum());
+    while let Some(Reverse((value, index, position))) = heap.pop() {
+        merged_array.push(value.clone());
+        if let Some(next) = arrays[index].get(position + 1) {
+            heap.push(Reverse((next, index, position + 1)));
+        }
+    }
+
+    merged_array
+}
+
 fn main() {
     let merged_array = merge_sorted_arrays(&[0, 3, 4, 31], &[3, 4, 6, 30]);
 
     println!("{:?}", merged_array);
+
+    let merged_array = merge_k_sorted_arrays(&[&[0, 3, 4, 31], &[3, 4, 6, 30], &[], &[1, 50]]);
+
+    println!("{:?}", merged_array);
 }
@@ -0,0 +1,381 @@
+/*
+  Assigning keys to a changing set of nodes (cache shards, workers) so that adding or removing
+  a node moves as few keys as possible.
+
+  The obvious `hash(key) % n` moves almost every key whenever n changes. The three schemes here
+  move only about 1/n of them:
+
+  - HashRing places each node at many pseudo-random points ("virtual nodes") on a circle of
+    hash values, and a key belongs to the first node point at or after the key's hash. A node's
+    weight scales its number of points, and so its share of the keys.
+  - RendezvousHasher scores every (key, node) pair by hash and picks the node with the highest
+    score. It needs no ring, but a lookup is O(nodes).
+  - jump_consistent_hash maps a key to one of n numbered buckets with no memory at all, but
+    buckets can only be added or removed at the end.
+
+  Node placement must agree between processes, so the default hasher is the FNV-1a from
+  hashers.rs rather than a randomly keyed one. Any `BuildHasher` can be used. Every hash goes
+  through `mix` before use: FNV-1a of inputs that differ only in their last byte, like
+  "node-a#1" and "node-a#2", differ mostly in the low bits, and positions on the ring depend on
+  the high bits too.
+*/
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+
+#[allow(dead_code)]
+#[path = "hashers.rs"]
+mod hashers;
+
+use hashers::{mix, BuildFnvHasher};
+
+/// A consistent-hash ring with virtual nodes.
+///
+/// A node of weight w is hashed to `w * points_per_weight` points on the ring. A key goes to
+/// the node owning the first point clockwise from the key's hash. Adding a node only takes
+/// over the keys just before its points, and removing one only hands its own keys to the next
+/// points along, so on average 1/n of the keys move.
+#[derive(Clone)]
+struct HashRing<N, S = BuildFnvHasher> {
+    /// Each point on the ring and the node it belongs to.
+    points: BTreeMap<u64, N>,
+    /// The nodes and their weights, in the order they were added.
+    nodes: Vec<(N, u32)>,
+    points_per_weight: u32,
+    hash_builder: S,
+}
+
+impl<N: Hash + Eq + Clone> HashRing<N> {
+    /// Creates an empty ring that hashes with FNV-1a.
+    ///
+    /// # Arguments
+    ///
+    /// * `points_per_weight` - The number of virtual nodes per unit of weight. More points
+    ///   spread the keys more evenly: with 100, node loads are typically within about 10% of
+    ///   their fair share.
+    pub fn new(points_per_weight: u32) -> Self {
+        Self::with_hasher(points_per_weight, BuildFnvHasher::default())
+    }
+}
+
+impl<N: Hash + Eq + Clone, S: BuildHasher> HashRing<N, S> {
+    /// Creates an empty ring that hashes with hashers built by `hash_builder`.
+    pub fn with_hasher(points_per_weight: u32, hash_builder: S) -> Self {
+        assert!(points_per_weight > 0, "a node needs at least one point");
+        HashRing {
+            points: BTreeMap::new(),
+            nodes: Vec::new(),
+            points_per_weight,
+            hash_builder,
+        }
+    }
+
+    /// Returns the number of nodes on the ring.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the ring has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Adds a node with the given weight, or changes the weight of a node already on the ring.
+    /// This operation runs in O(w log p) time, for w points added and p points on the ring.
+    pub fn add_node(&mut self, node: N, weight: u32) {
+        assert!(weight > 0, "a node's weight must be positive");
+        self.remove_node(&node);
+        for replica in 0..weight * self.points_per_weight {
+            let point = mix(self.hash_builder.hash_one((&node, replica)));
+            // On the rare clash of two points, the first one keeps it.
+            self.points.entry(point).or_insert_with(|| node.clone());
+        }
+        self.nodes.push((node, weight));
+    }
+
+    /// Removes a node, handing its keys to the nodes after its points.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the node was on the ring.
+    pub fn remove_node(&mut self, node: &N) -> bool {
+        let Some(position) = self.nodes.iter().position(|(n, _)| n == node) else {
+            return false;
+        };
+        let (node, weight) = self.nodes.remove(position);
+        for replica in 0..weight * self.points_per_weight {
+            let point = mix(self.hash_builder.hash_one((&node, replica)));
+            if self.points.get(&point) == Some(&node) {
+                self.points.remove(&point);
+            }
+        }
+        true
+    }
+
+    /// Returns the node that owns `key`, or `None` if the ring is empty.
+    /// This operation runs in O(log p) time.
+    pub fn node_for<K: Hash + ?Sized>(&self, key: &K) -> Option<&N> {
+        let hash = mix(self.hash_builder.hash_one(key));
+        self.points
+            .range(hash..)
+            .next()
+            .or_else(|| self.points.iter().next())
+            .map(|(_, node)| node)
+    }
+}
+
+/// Rendezvous, or highest-random-weight, hashing.
+///
+/// Every node scores the key with a hash of the pair, and the highest score wins. Removing a
+/// node only moves the keys it won, each to its runner-up, and a new node only takes the keys
+/// it now wins. With weights, a score is `-weight / ln(u)` for the hash as a fraction u in
+/// (0, 1), so each node wins in proportion to its weight.
+struct RendezvousHasher<N, S = BuildFnvHasher> {
+    nodes: Vec<(N, f64)>,
+    hash_builder: S,
+}
+
+impl<N: Hash + Eq> RendezvousHasher<N> {
+    /// Creates an empty hasher that hashes with FNV-1a.
+    pub fn new() -> Self {
+        Self::with_hasher(BuildFnvHasher::default())
+    }
+}
+
+impl<N: Hash + Eq, S: BuildHasher> RendezvousHasher<N, S> {
+    /// Creates an empty hasher that hashes with hashers built by `hash_builder`.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        RendezvousHasher {
+            nodes: Vec::new(),
+            hash_builder,
+        }
+    }
+
+    /// Adds a node with the given weight, or changes the weight of a node already present.
+    pub fn add_node(&mut self, node: N, weight: f64) {
+        assert!(weight > 0.0, "a node's weight must be positive");
+        self.remove_node(&node);
+        self.nodes.push((node, weight));
+    }
+
+    /// Removes a node.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the node was present.
+    pub fn remove_node(&mut self, node: &N) -> bool {
+        let length = self.nodes.len();
+        self.nodes.retain(|(n, _)| n != node);
+        self.nodes.len() < length
+    }
+
+    /// Returns the node with the highest score for `key`, or `None` if there are no nodes.
+    /// This operation runs in O(n) time.
+    pub fn node_for<K: Hash + ?Sized>(&self, key: &K) -> Option<&N> {
+        self.nodes
+            .iter()
+            .map(|(node, weight)| {
+                let hash = mix(self.hash_builder.hash_one((key, node)));
+                // The top 53 bits as a fraction strictly between 0 and 1.
+                let unit = ((hash >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+                (node, -weight / unit.ln())
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(node, _)| node)
+    }
+}
+
+/// Jump consistent hash (Lamping and Veach): maps a 64-bit key hash to a bucket in
+/// `0..buckets`, using no memory.
+///
+/// Going from n to n + 1 buckets moves exactly the keys that now land in bucket n, a
+/// 1/(n + 1) share. Buckets are numbered, so only the last one can be removed.
+/// This operation runs in O(log n) expected time.
+///
+/// # Panics
+///
+/// Panics if `buckets` is zero.
+fn jump_consistent_hash(mut key: u64, buckets: u32) -> u32 {
+    assert!(buckets > 0, "there must be at least one bucket");
+    let mut bucket: i64 = -1;
+    let mut next: i64 = 0;
+    while next < buckets as i64 {
+        bucket = next;
+        key = key.wrapping_mul(2_862_933_555_777_941_757).wrapping_add(1);
+        next = ((bucket + 1) as f64 * ((1u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+    bucket as u32
+}
+
+/// How many keys changed owner between two assignments, returned by `measure_movement`.
+struct MovementReport {
+    keys: usize,
+    moved: usize,
+    /// The share of keys that must move at the least, for comparison.
+    ideal: f64,
+}
+
+impl fmt::Display for MovementReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} keys moved ({:.1}%, ideal {:.1}%)",
+            self.moved,
+            self.keys,
+            100.0 * self.moved as f64 / self.keys as f64,
+            100.0 * self.ideal
+        )
+    }
+}
+
+/// Compares where every key goes before and after a topology change.
+///
+/// # Arguments
+///
+/// * `keys` - The keys to place.
+/// * `before` - The owner of a key before the change.
+/// * `after` - The owner of a key after the change.
+/// * `ideal` - The smallest share of keys the change must move, such as 1/5 when going from
+///   four equal nodes to five.
+fn measure_movement<K, N: PartialEq>(
+    keys: &[K],
+    before: impl Fn(&K) -> N,
+    after: impl Fn(&K) -> N,
+    ideal: f64,
+) -> MovementReport {
+    MovementReport {
+        keys: keys.len(),
+        moved: keys.iter().filter(|key| before(key) != after(key)).count(),
+        ideal,
+    }
+}
+
+/// Counts how many of `keys` each node owns.
+fn load<K>(keys: &[K], owner: impl Fn(&K) -> String) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for key in keys {
+        *counts.entry(owner(key)).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn main() {
+    let keys: Vec<String> = (0..20_000).map(|i| format!("user:{}", i)).collect();
+    let hasher = BuildFnvHasher::default();
+
+    let mut ring = HashRing::new(100);
+    for node in ["cache-a", "cache-b", "cache-c", "cache-d"] {
+        ring.add_node(node.to_string(), 1);
+    }
+    let owner = |ring: &HashRing<String>, key: &String| ring.node_for(key).unwrap().clone();
+    println!(
+        "Ring of {} nodes, load: {:?}",
+        ring.len(),
+        load(&keys, |key| owner(&ring, key))
+    );
+
+    let four = ring.clone();
+    ring.add_node("cache-e".to_string(), 1);
+    println!(
+        "Ring, adding a fifth node: {}",
+        measure_movement(&keys, |k| owner(&four, k), |k| owner(&ring, k), 1.0 / 5.0)
+    );
+    let five = ring.clone();
+    ring.remove_node(&"cache-b".to_string());
+    println!(
+        "Ring, removing cache-b: {}",
+        measure_movement(&keys, |k| owner(&five, k), |k| owner(&ring, k), 1.0 / 5.0)
+    );
+    let without_b = ring.clone();
+    ring.add_node("cache-a".to_string(), 3);
+    println!(
+        "Ring, cache-a weighted 3 of 6: {:?}, {}",
+        load(&keys, |key| owner(&ring, key)),
+        measure_movement(
+            &keys,
+            |k| owner(&without_b, k),
+            |k| owner(&ring, k),
+            1.0 / 4.0
+        )
+    );
+
+    let mut rendezvous = RendezvousHasher::new();
+    for node in ["cache-a", "cache-b", "cache-c", "cache-d"] {
+        rendezvous.add_node(node, 1.0);
+    }
+    let before: Vec<&str> = keys
+        .iter()
+        .map(|key| *rendezvous.node_for(key).unwrap())
+        .collect();
+    rendezvous.add_node("cache-e", 1.0);
+    let index = |key: &String| key[5..].parse::<usize>().unwrap();
+    println!(
+        "Rendezvous, adding a fifth node: {}",
+        measure_movement(
+            &keys,
+            |k| before[index(k)],
+            |k| *rendezvous.node_for(k).unwrap(),
+            1.0 / 5.0
+        )
+    );
+    let five: Vec<&str> = keys
+        .iter()
+        .map(|key| *rendezvous.node_for(key).unwrap())
+        .collect();
+    rendezvous.remove_node(&"cache-b");
+    rendezvous.add_node("cache-c", 2.0);
+    println!(
+        "Rendezvous, removing cache-b and doubling cache-c: {:?}, {}",
+        load(&keys, |key| rendezvous.node_for(key).unwrap().to_string()),
+        measure_movement(
+            &keys,
+            |k| five[index(k)],
+            |k| *rendezvous.node_for(k).unwrap(),
+            1.0 / 5.0
+        )
+    );
+
+    println!(
+        "Jump hash, 4 to 5 buckets: {}",
+        measure_movement(
+            &keys,
+            |k| jump_consistent_hash(hasher.hash_one(k), 4),
+            |k| jump_consistent_hash(hasher.hash_one(k), 5),
+            1.0 / 5.0
+        )
+    );
+    println!(
+        "Jump hash, 100 to 99 buckets: {}",
+        measure_movement(
+            &keys,
+            |k| jump_consistent_hash(hasher.hash_one(k), 100),
+            |k| jump_consistent_hash(hasher.hash_one(k), 99),
+            1.0 / 100.0
+        )
+    );
+    println!(
+        "hash % n, 4 to 5 nodes: {}",
+        measure_movement(
+            &keys,
+            |k| mix(hasher.hash_one(k)) % 4,
+            |k| mix(hasher.hash_one(k)) % 5,
+            1.0 / 5.0
+        )
+    );
+
+    let mut keyed = HashRing::with_hasher(10, std::collections::hash_map::RandomState::new());
+    println!("Empty ring: {:?}", keyed.node_for("key"));
+    keyed.add_node(1, 1);
+    println!("One-node ring: {:?}", keyed.node_for("key"));
+    let removed = keyed.remove_node(&1);
+    println!(
+        "Removed: {}, again: {}, empty: {}",
+        removed,
+        keyed.remove_node(&1),
+        keyed.is_empty()
+    );
+    let mut custom = RendezvousHasher::with_hasher(BuildFnvHasher::default());
+    custom.add_node('x', 1.0);
+    println!("Rendezvous with one node: {:?}", custom.node_for(&42));
+}
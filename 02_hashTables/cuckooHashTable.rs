@@ -0,0 +1,560 @@
+/*
+  Cuckoo hashing
+
+  Chaining and open addressing both have lookups that are O(1) only on average: an unlucky key
+  may sit at the end of a long chain or probe sequence. A cuckoo table gives every key a fixed
+  set of `d` candidate buckets, one per hash function, and guarantees the key is in one of them.
+  A lookup therefore checks at most d buckets of b slots each, no matter what.
+
+  Inserts pay for that guarantee. If all candidate buckets are full, the new key evicts a
+  resident (like a cuckoo chick pushing an egg out of the nest), and the evicted key moves to
+  one of its own other buckets, possibly evicting someone else in turn. If the chain of
+  evictions grows past a limit, the table assumes it is going round in a cycle, picks new hash
+  functions and rebuilds itself, growing if it is reasonably full.
+
+  With d = 2 hash functions and b = 4 slots per bucket, tables stay insertable up to ~90% full.
+*/
+
+use std::hash::{BuildHasher, Hash};
+
+#[allow(dead_code)]
+#[path = "hashers.rs"]
+mod hashers;
+
+use hashers::DefaultHashBuilder;
+
+/// The number of hash functions used by `CuckooHashTable::new`.
+const DEFAULT_HASH_FUNCTIONS: usize = 2;
+
+/// The number of slots per bucket used by `CuckooHashTable::new`.
+const DEFAULT_SLOTS_PER_BUCKET: usize = 4;
+
+/// The longest chain of evictions an insert may trigger before the table is rebuilt.
+const MAX_KICKS: usize = 500;
+
+/// The largest ratio of entries to slots allowed before the table grows.
+const MAX_LOAD_FACTOR: f64 = 0.9;
+
+/// The bucket count used the first time an empty table grows.
+const MIN_BUCKETS: usize = 4;
+
+/// What a failed placement leaves behind: the entry being inserted, and the older entry that
+/// was evicted last, if the new one had already found a slot.
+type Homeless<K, V> = ((K, V), Option<(K, V)>);
+
+/// A hash table with worst-case O(1) lookups, using bucketized cuckoo hashing.
+struct CuckooHashTable<K, V, S = DefaultHashBuilder> {
+    /// `bucket_count * slots_per_bucket` slots; bucket `i` owns a contiguous run of them.
+    slots: Vec<Option<(K, V)>>,
+    slots_per_bucket: usize,
+    hash_functions: usize,
+    /// The number of key-value pairs stored in the table.
+    length: usize,
+    /// Mixed into every hash; changing it picks a fresh family of hash functions.
+    seed: u64,
+    /// State of the xorshift generator that picks eviction victims.
+    rng: u64,
+    /// How many times the table has been rebuilt.
+    rehashes: usize,
+    hash_builder: S,
+}
+
+/// A view into a single key of a `CuckooHashTable`, which may or may not hold a value.
+/// Returned by `CuckooHashTable::entry`.
+enum Entry<'a, K, V, S> {
+    Occupied(OccupiedEntry<'a, K, V, S>),
+    Vacant(VacantEntry<'a, K, V, S>),
+}
+
+/// An entry whose key is present in the table.
+struct OccupiedEntry<'a, K, V, S> {
+    table: &'a mut CuckooHashTable<K, V, S>,
+    slot: usize,
+}
+
+/// An entry whose key is absent from the table.
+struct VacantEntry<'a, K, V, S> {
+    table: &'a mut CuckooHashTable<K, V, S>,
+    key: K,
+}
+
+impl<K, V> CuckooHashTable<K, V>
+where
+    K: Eq + Hash,
+{
+    /// Creates a new cuckoo hash table with two hash functions and four slots per bucket.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The initial number of buckets. A size of 0 is allowed.
+    pub fn new(size: usize) -> Self {
+        Self::with_layout(size, DEFAULT_HASH_FUNCTIONS, DEFAULT_SLOTS_PER_BUCKET)
+    }
+
+    /// Creates a new cuckoo hash table with a custom layout.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The initial number of buckets.
+    /// * `hash_functions` - How many candidate buckets each key has. Must be at least 2.
+    /// * `slots_per_bucket` - How many entries fit in one bucket. Must be at least 1.
+    pub fn with_layout(size: usize, hash_functions: usize, slots_per_bucket: usize) -> Self {
+        Self::with_layout_and_hasher(
+            size,
+            hash_functions,
+            slots_per_bucket,
+            DefaultHashBuilder::default(),
+        )
+    }
+}
+
+impl<K, V, S> CuckooHashTable<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Creates a new cuckoo hash table that hashes its keys with hashers built by `hash_builder`.
+    pub fn with_hasher(size: usize, hash_builder: S) -> Self {
+        Self::with_layout_and_hasher(
+            size,
+            DEFAULT_HASH_FUNCTIONS,
+            DEFAULT_SLOTS_PER_BUCKET,
+            hash_builder,
+        )
+    }
+
+    /// Creates a new cuckoo hash table with a custom layout and a custom hash function.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The initial number of buckets.
+    /// * `hash_functions` - How many candidate buckets each key has. Must be at least 2.
+    /// * `slots_per_bucket` - How many entries fit in one bucket. Must be at least 1.
+    /// * `hash_builder` - Creates the hasher the hash functions are derived from.
+    pub fn with_layout_and_hasher(
+        size: usize,
+        hash_functions: usize,
+        slots_per_bucket: usize,
+        hash_builder: S,
+    ) -> Self {
+        assert!(
+            hash_functions >= 2,
+            "cuckoo hashing needs two hash functions"
+        );
+        assert!(slots_per_bucket >= 1, "buckets need at least one slot");
+        CuckooHashTable {
+            slots: Self::empty_slots(size * slots_per_bucket),
+            slots_per_bucket,
+            hash_functions,
+            length: 0,
+            seed: 0,
+            rng: 0x2545_f491_4f6c_dd1d,
+            rehashes: 0,
+            hash_builder,
+        }
+    }
+
+    fn empty_slots(count: usize) -> Vec<Option<(K, V)>> {
+        let mut slots = Vec::with_capacity(count);
+        slots.resize_with(count, || None);
+        slots
+    }
+
+    /// Returns the number of key-value pairs stored in the table.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the table holds no key-value pairs.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns the number of buckets currently allocated.
+    pub fn bucket_count(&self) -> usize {
+        self.slots.len() / self.slots_per_bucket
+    }
+
+    /// Returns the current ratio of entries to slots.
+    pub fn load_factor(&self) -> f64 {
+        if self.slots.is_empty() {
+            0.0
+        } else {
+            self.length as f64 / self.slots.len() as f64
+        }
+    }
+
+    /// Returns how many times the table has been rebuilt after an eviction cycle or to grow.
+    pub fn rehash_count(&self) -> usize {
+        self.rehashes
+    }
+
+    /// Returns the bucket chosen for `key` by the `i`-th hash function.
+    fn bucket_index(&self, key: &K, i: usize) -> usize {
+        let hash = self.hash_builder.hash_one((self.seed, i, key));
+        (hash as usize) % self.bucket_count()
+    }
+
+    /// Returns the slots of a bucket.
+    fn bucket_slots(&self, bucket: usize) -> std::ops::Range<usize> {
+        bucket * self.slots_per_bucket..(bucket + 1) * self.slots_per_bucket
+    }
+
+    /// Finds the slot holding `key`. This checks at most d buckets, so it is O(1) in the worst case.
+    fn find(&self, key: &K) -> Option<usize> {
+        if self.slots.is_empty() {
+            return None;
+        }
+        (0..self.hash_functions)
+            .flat_map(|i| self.bucket_slots(self.bucket_index(key, i)))
+            .find(|&slot| matches!(&self.slots[slot], Some((k, _)) if k == key))
+    }
+
+    /// Returns the next value of a xorshift pseudo-random sequence.
+    fn next_random(&mut self) -> usize {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng as usize
+    }
+
+    /// Places an entry whose key is absent, evicting residents as needed.
+    ///
+    /// # Returns
+    ///
+    /// The slot the entry ended up in. If the chain of evictions exceeded `MAX_KICKS`, returns
+    /// `Err` with the entry taken back out of the table, plus the older entry that was left
+    /// without a slot, if any. Every other entry is still in the table.
+    fn try_place(&mut self, entry: (K, V)) -> Result<usize, Homeless<K, V>> {
+        let mut carried = entry;
+        let mut carrying_original = true;
+        let mut original_slot = 0;
+
+        for _ in 0..MAX_KICKS {
+            for i in 0..self.hash_functions {
+                let bucket = self.bucket_index(&carried.0, i);
+                let free = self.bucket_slots(bucket).find(|&s| self.slots[s].is_none());
+                if let Some(slot) = free {
+                    self.slots[slot] = Some(carried);
+                    return Ok(if carrying_original {
+                        slot
+                    } else {
+                        original_slot
+                    });
+                }
+            }
+
+            // Every candidate bucket is full: evict a random resident of a random one.
+            let r = self.next_random();
+            let bucket = self.bucket_index(&carried.0, r % self.hash_functions);
+            let slot =
+                bucket * self.slots_per_bucket + (r / self.hash_functions) % self.slots_per_bucket;
+            carried = self.slots[slot].replace(carried).unwrap();
+
+            // Keep track of where the new entry is, in case an eviction chain loops back to it.
+            if carrying_original {
+                original_slot = slot;
+                carrying_original = false;
+            } else if slot == original_slot {
+                carrying_original = true;
+            }
+        }
+
+        if carrying_original {
+            Err((carried, None))
+        } else {
+            let original = self.slots[original_slot].take().unwrap();
+            Err((original, Some(carried)))
+        }
+    }
+
+    /// Rebuilds the table with fresh hash functions and `bucket_count` buckets.
+    /// If an insert fails during the rebuild, the bucket count doubles and it starts over.
+    fn rehash(&mut self, mut bucket_count: usize, mut entries: Vec<(K, V)>) {
+        entries.extend(self.slots.drain(..).flatten());
+        loop {
+            self.seed += 1;
+            self.rehashes += 1;
+            self.slots = Self::empty_slots(bucket_count * self.slots_per_bucket);
+
+            let mut pending = entries.into_iter();
+            let failed = pending
+                .by_ref()
+                .find_map(|entry| self.try_place(entry).err());
+            match failed {
+                None => return,
+                Some((original, homeless)) => {
+                    entries = pending.collect();
+                    entries.push(original);
+                    entries.extend(homeless);
+                    entries.extend(self.slots.drain(..).flatten());
+                    bucket_count *= 2;
+                }
+            }
+        }
+    }
+
+    /// Inserts an entry whose key is absent, growing or rebuilding the table as needed.
+    ///
+    /// # Returns
+    ///
+    /// The slot holding the new entry.
+    fn insert_new(&mut self, key: K, value: V) -> usize {
+        let capacity = self.slots.len() as f64;
+        if (self.length + 1) as f64 > capacity * MAX_LOAD_FACTOR {
+            let bucket_count = (self.bucket_count() * 2).max(MIN_BUCKETS);
+            self.rehash(bucket_count, Vec::new());
+        }
+
+        let mut entry = (key, value);
+        loop {
+            match self.try_place(entry) {
+                Ok(slot) => {
+                    self.length += 1;
+                    return slot;
+                }
+                Err((original, homeless)) => {
+                    // An eviction cycle. New hash functions usually break it; if the table is
+                    // already fairly full, grow at the same time.
+                    let bucket_count = if self.load_factor() < 0.5 {
+                        self.bucket_count()
+                    } else {
+                        self.bucket_count() * 2
+                    };
+                    self.rehash(bucket_count, homeless.into_iter().collect());
+                    entry = original;
+                }
+            }
+        }
+    }
+
+    /// Inserts a key-value pair into the table, replacing the value if the key already exists.
+    /// This operation runs in O(1) amortized expected time.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to insert.
+    /// * `value` - The value associated with the key.
+    ///
+    /// # Returns
+    ///
+    /// The value previously associated with the key, or `None` if the key is new.
+    pub fn set(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Retrieves a reference to the value stored under `key`.
+    /// This operation runs in O(1) time in the worst case.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let slot = self.find(key)?;
+        self.slots[slot].as_ref().map(|(_, value)| value)
+    }
+
+    /// Retrieves a mutable reference to the value stored under `key`.
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let slot = self.find(key)?;
+        self.slots[slot].as_mut().map(|(_, value)| value)
+    }
+
+    /// Returns `true` if the table holds a value for `key`.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.find(key).is_some()
+    }
+
+    /// Removes `key` from the table. Emptying a slot never disturbs other keys, so no
+    /// tombstones are needed. This operation runs in O(1) time in the worst case.
+    ///
+    /// # Returns
+    ///
+    /// The value that was associated with the key, or `None` if the key does not exist.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let slot = self.find(key)?;
+        self.length -= 1;
+        self.slots[slot].take().map(|(_, value)| value)
+    }
+
+    /// Gets the entry for the given key, for in-place inspection and modification.
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S> {
+        match self.find(&key) {
+            Some(slot) => Entry::Occupied(OccupiedEntry { table: self, slot }),
+            None => Entry::Vacant(VacantEntry { table: self, key }),
+        }
+    }
+
+    /// Returns an iterator over all key-value pairs, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.slots.iter().flatten().map(|(key, value)| (key, value))
+    }
+
+    /// Returns an iterator over all keys stored in the table.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over all values stored in the table.
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+}
+
+impl<'a, K, V, S> Entry<'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Returns the key of this entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => &entry.key,
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant, and returns a mutable reference to the value.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the result of `default` if the entry is vacant, and returns a mutable reference to the value.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Inserts `V::default()` if the entry is vacant, and returns a mutable reference to the value.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Calls `f` on the value if the entry is occupied, and returns the entry for further chaining.
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K, V, S> OccupiedEntry<'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn pair(&self) -> &(K, V) {
+        self.table.slots[self.slot].as_ref().unwrap()
+    }
+
+    /// Returns the key of this entry.
+    pub fn key(&self) -> &K {
+        &self.pair().0
+    }
+
+    /// Returns a reference to the value of this entry.
+    pub fn get(&self) -> &V {
+        &self.pair().1
+    }
+
+    /// Returns a mutable reference to the value of this entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.table.slots[self.slot].as_mut().unwrap().1
+    }
+
+    /// Converts the entry into a mutable reference to its value, tied to the table's lifetime.
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.table.slots[self.slot].as_mut().unwrap().1
+    }
+
+    /// Replaces the value of this entry and returns the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes this entry from the table and returns its key and value.
+    pub fn remove(self) -> (K, V) {
+        self.table.length -= 1;
+        self.table.slots[self.slot].take().unwrap()
+    }
+}
+
+impl<'a, K, V, S> VacantEntry<'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Inserts a value under this entry's key and returns a mutable reference to it.
+    /// Other entries may be evicted to make room, and the table may be rebuilt.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let table = self.table;
+        let slot = table.insert_new(self.key, value);
+        &mut table.slots[slot].as_mut().unwrap().1
+    }
+}
+
+fn main() {
+    let mut my_hash_table = CuckooHashTable::new(0);
+    my_hash_table.set("grapes", 10000);
+    my_hash_table.set("apples", 54);
+    my_hash_table.set("oranges", 2);
+    println!("Value for 'grapes': {:?}", my_hash_table.get(&"grapes"));
+    println!(
+        "Old value for 'grapes': {:?}",
+        my_hash_table.set("grapes", 5)
+    );
+    if let Some(value) = my_hash_table.get_mut(&"apples") {
+        *value += 1;
+    }
+    println!("Removed 'oranges': {:?}", my_hash_table.remove(&"oranges"));
+    println!(
+        "Contains 'oranges': {}",
+        my_hash_table.contains_key(&"oranges")
+    );
+    println!("Keys: {:?}", my_hash_table.keys().collect::<Vec<_>>());
+
+    // Fill a table with three hash functions and two slots per bucket.
+    let mut table = CuckooHashTable::with_layout(0, 3, 2);
+    for i in 0..100_000u64 {
+        table.set(i, i);
+    }
+    let hits = (0..200_000u64).filter(|i| table.contains_key(i)).count();
+    println!(
+        "{} hits, {} entries in {} buckets (load factor {:.2}), {} rebuilds, sum {}",
+        hits,
+        table.len(),
+        table.bucket_count(),
+        table.load_factor(),
+        table.rehash_count(),
+        table.values().sum::<u64>()
+    );
+
+    let mut word_counts = CuckooHashTable::with_hasher(0, DefaultHashBuilder::default());
+    let text = "the quick brown fox jumps over the lazy dog the end";
+    for word in text.split_whitespace() {
+        word_counts
+            .entry(word)
+            .and_modify(|count| *count += 1)
+            .or_insert(1);
+    }
+    *word_counts.entry("fox").or_default() += 10;
+    if let Entry::Occupied(entry) = word_counts.entry("end") {
+        if *entry.get() == 1 {
+            println!("Removed entry: {:?}", entry.remove());
+        }
+    }
+    let the = word_counts.entry("the");
+    println!("Counted '{}'", the.key());
+    for (word, count) in word_counts.iter() {
+        println!("{}: {}", word, count);
+    }
+    println!("Empty: {}", word_counts.is_empty());
+}
@@ -16,6 +16,18 @@ Bonus... What if we had this:
 
 */
 
+use std::hash::Hash;
+
+#[allow(dead_code)]
+#[path = "hashSet.rs"]
+mod hash_set;
+
+use hash_set::hash_table::HashTable;
+use hash_set::HashSet;
+
+/// Returns the earliest element that appears again later, by comparing every pair.
+/// This runs in O(n^2) time. For [2,5,5,2,3,5,1,2,4] it returns 2, because 2 comes first,
+/// even though the pair of 5s closes sooner.
 fn first_recurring_character(input: &[i32]) -> Option<i32> {
     for i in 0..input.len() {
         for j in i + 1..input.len() {
@@ -27,18 +39,137 @@ fn first_recurring_character(input: &[i32]) -> Option<i32> {
     None
 }
 
+/// Returns the element whose second occurrence comes first, remembering the elements seen so
+/// far in a hash table. This runs in O(n) time, and answers the bonus question: for
+/// [2,5,5,2,3,5,1,2,4] it returns 5, because the pair of 5s closes before the pair of 2s.
 fn first_recurring_character2(input: &[i32]) -> Option<i32> {
-    let mut map = std::collections::HashMap::new();
+    let mut map = HashTable::new(0);
     for (i, &num) in input.iter().enumerate() {
         if let Some(&index) = map.get(&num) {
             return Some(input[index]);
         } else {
-            map.insert(num, i);
+            map.set(num, i);
         }
     }
     None
 }
 
+/// An iterator adaptor that yields each item the moment it occurs for the second time,
+/// without collecting the input first. Created by `RecurringExt::recurring`.
+struct Recurring<I: Iterator> {
+    iter: I,
+    /// Every item seen so far, and whether it has already been yielded as recurring.
+    seen: HashTable<I::Item, bool>,
+}
+
+impl<I> Iterator for Recurring<I>
+where
+    I: Iterator,
+    I::Item: Hash + Eq,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for item in self.iter.by_ref() {
+            match self.seen.get_mut(&item) {
+                Some(reported) if !*reported => {
+                    *reported = true;
+                    return Some(item);
+                }
+                Some(_) => {}
+                None => {
+                    self.seen.set(item, false);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Adds `recurring` to every iterator.
+trait RecurringExt: Iterator + Sized {
+    /// Yields the items that occur more than once, each when its second occurrence is reached.
+    /// Calling `next` once gives the first recurring item, in the sense of
+    /// `first_recurring_character2`, after reading only as much input as needed.
+    fn recurring(self) -> Recurring<Self>
+    where
+        Self::Item: Hash + Eq,
+    {
+        Recurring {
+            iter: self,
+            seen: HashTable::new(0),
+        }
+    }
+}
+
+impl<I: Iterator> RecurringExt for I {}
+
+/// Returns the first item that occurs exactly once in the whole input.
+/// Every item has to be read before any can be called unique, so this runs in O(n) time and
+/// O(d) memory for d distinct items.
+fn first_unique<T, I>(input: I) -> Option<T>
+where
+    T: Hash + Eq,
+    I: IntoIterator<Item = T>,
+{
+    // Each distinct item maps to the position where it first appeared and its count.
+    let mut counts: HashTable<T, (usize, usize)> = HashTable::new(0);
+    for (position, item) in input.into_iter().enumerate() {
+        counts.entry(item).or_insert((position, 0)).1 += 1;
+    }
+    counts
+        .into_iter()
+        .filter(|(_, (_, count))| *count == 1)
+        .min_by_key(|(_, (position, _))| *position)
+        .map(|(item, _)| item)
+}
+
+/// Returns `true` if two equal items are at most `k` positions apart.
+/// Generalizes `contains_duplicate` from 01_arrays/containsDuplicate.rs, which is the case of
+/// unlimited `k`. A set holds the last `k` items, so this runs in O(n) time and O(k) memory.
+fn contains_nearby_duplicate<T: Hash + Eq>(items: &[T], k: usize) -> bool {
+    let mut window = HashSet::new();
+    for (position, item) in items.iter().enumerate() {
+        if !window.insert(item) {
+            return true;
+        }
+        if position >= k {
+            window.remove(&&items[position - k]);
+        }
+    }
+    false
+}
+
+/// Returns `true` if two items at most `k` positions apart differ by at most `t`.
+///
+/// The last `k` values are sorted into buckets `t + 1` wide, so two values in the same bucket
+/// are always close enough, and a close value can otherwise only sit in one of the two
+/// neighbouring buckets. Each bucket holds at most one value (a second would already be a
+/// match), so every step checks three buckets, and this runs in O(n) time and O(k) memory.
+fn contains_nearby_almost_duplicate(values: &[i64], k: usize, t: u64) -> bool {
+    // Widened so that neither the bucket width nor the differences can overflow.
+    let width = t as i128 + 1;
+    let bucket_of = |value: i64| (value as i128).div_euclid(width);
+    let mut buckets: HashTable<i128, i64> = HashTable::new(0);
+
+    for (position, &value) in values.iter().enumerate() {
+        let bucket = bucket_of(value);
+        let close = (bucket - 1..=bucket + 1).any(|neighbour| {
+            buckets
+                .get(&neighbour)
+                .is_some_and(|&other| (value as i128 - other as i128).unsigned_abs() <= t as u128)
+        });
+        if close {
+            return true;
+        }
+        buckets.set(bucket, value);
+        if position >= k {
+            buckets.remove(&bucket_of(values[position - k]));
+        }
+    }
+    false
+}
+
 fn main() {
     let input = [1, 5, 5, 1, 3, 4, 6];
     if let Some(result) = first_recurring_character2(&input) {
@@ -46,4 +177,39 @@ fn main() {
     } else {
         println!("No recurring character found");
     }
+
+    let bonus = [2, 5, 5, 2, 3, 5, 1, 2, 4];
+    println!(
+        "Bonus: earliest repeated {:?}, first pair to close {:?}",
+        first_recurring_character(&bonus),
+        first_recurring_character2(&bonus)
+    );
+
+    // The adaptor stops reading as soon as it has an answer, so it works on endless input.
+    let collatz = std::iter::successors(Some(27u64), |&n| {
+        Some(if n % 2 == 0 { n / 2 } else { 3 * n + 1 })
+    });
+    println!(
+        "First value the Collatz sequence from 27 revisits: {:?}",
+        collatz.recurring().next()
+    );
+    let repeats: Vec<char> = "mississippi".chars().recurring().collect();
+    println!(
+        "Letters of 'mississippi' in the order they repeat: {:?}",
+        repeats
+    );
+    println!("First unique letter: {:?}", first_unique("swiss".chars()));
+
+    let readings = [1, 2, 3, 1, 2, 3];
+    println!(
+        "Duplicate within 2: {}, within 3: {}",
+        contains_nearby_duplicate(&readings, 2),
+        contains_nearby_duplicate(&readings, 3)
+    );
+    let prices = [100, 250, 180, 104];
+    println!(
+        "Prices within 5 of each other at most 2 apart: {}, 3 apart: {}",
+        contains_nearby_almost_duplicate(&prices, 2, 5),
+        contains_nearby_almost_duplicate(&prices, 3, 5)
+    );
 }
@@ -0,0 +1,264 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::ops::{BitAnd, BitOr, BitXor, Sub};
+
+#[allow(dead_code)]
+#[path = "hashTableImplementation.rs"]
+pub mod hash_table;
+
+use hash_table::hashers::DefaultHashBuilder;
+use hash_table::HashTable;
+
+/// A set of unique values, stored as the keys of a `HashTable` with no values.
+///
+/// Besides the usual insert, remove and lookup in O(1) average time, it supports set algebra:
+/// `union`, `intersection`, `difference` and `symmetric_difference` return lazy iterators
+/// that borrow both sets, while the `|`, `&`, `-` and `^` operators build a new set.
+pub struct HashSet<T, S = DefaultHashBuilder> {
+    table: HashTable<T, (), S>,
+}
+
+impl<T> HashSet<T>
+where
+    T: Eq + Hash,
+{
+    /// Creates a new, empty set. No memory is allocated until the first insert.
+    pub fn new() -> Self {
+        Self::with_hasher(DefaultHashBuilder::default())
+    }
+}
+
+impl<T, S> HashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Creates a new, empty set that hashes its values with hashers built by `hash_builder`.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        HashSet {
+            table: HashTable::with_hasher(0, hash_builder),
+        }
+    }
+
+    /// Returns the number of values in the set.
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Returns `true` if the set holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.table.len() == 0
+    }
+
+    /// Adds a value to the set.
+    /// This operation runs in O(1) amortized time.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the value was not already present.
+    pub fn insert(&mut self, value: T) -> bool {
+        self.table.set(value, ()).is_none()
+    }
+
+    /// Removes a value from the set.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the value was present.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.table.remove(value).is_some()
+    }
+
+    /// Returns `true` if the set holds `value`.
+    /// This operation runs in O(1) average time.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.table.contains_key(value)
+    }
+
+    /// Returns an iterator over the values, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.table.keys()
+    }
+
+    /// Returns a lazy iterator over the values in `self`, `other` or both, each once.
+    /// Walking it takes O(n + m) time.
+    pub fn union<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a T> + 'a {
+        self.iter().chain(other.difference(self))
+    }
+
+    /// Returns a lazy iterator over the values in both `self` and `other`.
+    /// It walks the smaller set and looks each value up in the larger, so it takes
+    /// O(min(n, m)) time.
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a T> + 'a {
+        let (smaller, larger) = if self.len() <= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        smaller.iter().filter(move |value| larger.contains(value))
+    }
+
+    /// Returns a lazy iterator over the values in `self` but not in `other`.
+    pub fn difference<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a T> + 'a {
+        self.iter().filter(move |value| !other.contains(value))
+    }
+
+    /// Returns a lazy iterator over the values in exactly one of `self` and `other`.
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a T> + 'a {
+        self.difference(other).chain(other.difference(self))
+    }
+
+    /// Returns `true` if every value of `self` is also in `other`.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.iter().all(|value| other.contains(value))
+    }
+
+    /// Returns `true` if every value of `other` is also in `self`.
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns `true` if `self` and `other` have no values in common.
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).next().is_none()
+    }
+}
+
+impl<T, S> Default for HashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn default() -> Self {
+        HashSet::with_hasher(S::default())
+    }
+}
+
+impl<T, S> FromIterator<T> for HashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = HashSet::with_hasher(S::default());
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T, S> Extend<T> for HashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T, S> PartialEq for HashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Two sets are equal if they hold the same values, whatever their hashers or bucket counts.
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.is_subset(other)
+    }
+}
+
+impl<T, S> fmt::Debug for HashSet<T, S>
+where
+    T: Eq + Hash + fmt::Debug,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Implements a set operator on references, collecting the matching lazy iterator into a new set.
+macro_rules! set_operator {
+    ($trait:ident, $method:ident, $iterator:ident) => {
+        impl<T, S> $trait<&HashSet<T, S>> for &HashSet<T, S>
+        where
+            T: Eq + Hash + Clone,
+            S: BuildHasher + Default,
+        {
+            type Output = HashSet<T, S>;
+
+            fn $method(self, other: &HashSet<T, S>) -> HashSet<T, S> {
+                self.$iterator(other).cloned().collect()
+            }
+        }
+    };
+}
+
+set_operator!(BitOr, bitor, union);
+set_operator!(BitAnd, bitand, intersection);
+set_operator!(Sub, sub, difference);
+set_operator!(BitXor, bitxor, symmetric_difference);
+
+/// Returns the values of `set`, sorted, so the demo prints them in a fixed order.
+fn sorted<T: Eq + Hash + Ord + Clone>(set: &HashSet<T>) -> Vec<T> {
+    let mut values: Vec<T> = set.iter().cloned().collect();
+    values.sort();
+    values
+}
+
+fn main() {
+    let mut languages = HashSet::new();
+    languages.insert("rust");
+    languages.insert("go");
+    println!("Inserted 'rust' again: {}", languages.insert("rust"));
+    println!(
+        "Contains 'go': {}, removed 'go': {}, contains 'go': {}",
+        languages.contains(&"go"),
+        languages.remove(&"go"),
+        languages.contains(&"go")
+    );
+    println!("{:?} ({} values)", languages, languages.len());
+
+    let evens: HashSet<i32> = (0..20).step_by(2).collect();
+    let threes: HashSet<i32> = (0..20).step_by(3).collect();
+
+    let mut both: Vec<_> = evens.intersection(&threes).collect();
+    both.sort();
+    println!("Even multiples of three: {:?}", both);
+    println!("Union has {} values", evens.union(&threes).count());
+    println!("Only even: {}", evens.difference(&threes).count());
+    println!(
+        "In exactly one: {}",
+        evens.symmetric_difference(&threes).count()
+    );
+
+    println!("evens | threes = {:?}", sorted(&(&evens | &threes)));
+    println!("evens & threes = {:?}", sorted(&(&evens & &threes)));
+    println!("evens - threes = {:?}", sorted(&(&evens - &threes)));
+    println!("evens ^ threes = {:?}", sorted(&(&evens ^ &threes)));
+
+    let sixes: HashSet<i32> = (0..20).step_by(6).collect();
+    let odds: HashSet<i32> = (1..20).step_by(2).collect();
+    println!(
+        "Sixes are a subset of evens: {}, evens a superset of sixes: {}",
+        sixes.is_subset(&evens),
+        evens.is_superset(&sixes)
+    );
+    println!("Evens and odds are disjoint: {}", evens.is_disjoint(&odds));
+    println!(
+        "evens & threes == sixes: {}, odds empty: {}",
+        &evens & &threes == sixes,
+        odds.is_empty()
+    );
+}
@@ -1,41 +1,762 @@
-struct HashTable {
-    data: Vec<Option<(String, i32)>>,
+/*
+  Open addressing
+
+  hashTableImplementation.rs resolves collisions with separate chaining: every bucket is a
+  vector and colliding keys share it. Open addressing stores every entry directly in one flat
+  array instead. When a key's home slot is taken, the table walks a "probe sequence" of other
+  slots until it finds the key or a free slot:
+
+    Linear          home, home + 1, home + 2, ...
+    Quadratic       home, home + 1, home + 3, home + 6, ...   (triangular numbers)
+    DoubleHashing   home, home + step, home + 2 * step, ...   (step comes from a second hash)
+    RobinHood       linear, but an entry that has travelled further from home takes the slot
+                    of one that has travelled less, which keeps probe lengths even
+
+  Removing an entry cannot simply empty its slot, or lookups for keys further along the same
+  probe sequence would stop early. The first three strategies leave a tombstone behind that
+  lookups skip over and inserts may reuse. Robin Hood instead shifts the following entries back
+  by one slot ("backward-shift deletion"), so it never needs tombstones.
+
+  The capacity is always a power of two, which lets the quadratic sequence and any odd
+  double-hashing step visit every slot.
+*/
+
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+
+#[allow(dead_code)]
+#[path = "hashers.rs"]
+mod hashers;
+
+use hashers::{BuildFnvHasher, BuildPolynomialHasher, DefaultHashBuilder, SeededSipHash};
+
+/// The collision resolution strategy of an `OpenHashTable`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Probing {
+    Linear,
+    Quadratic,
+    DoubleHashing,
+    RobinHood,
+}
+
+/// A single slot of the table.
+enum Slot<K, V> {
+    Empty,
+    /// A removed entry. Lookups keep probing past it; inserts may reuse it.
+    Tombstone,
+    Occupied {
+        hash: u64,
+        key: K,
+        value: V,
+    },
+}
+
+/// A hash table that stores its entries directly in the slot array (open addressing).
+/// Like `HashTable`, the hash function is pluggable through the `S: BuildHasher` parameter.
+struct OpenHashTable<K, V, S = DefaultHashBuilder> {
+    slots: Vec<Slot<K, V>>,
+    /// The number of key-value pairs stored in the table.
+    length: usize,
+    /// The number of slots holding a tombstone.
+    tombstones: usize,
+    probing: Probing,
+    /// The largest ratio of used slots (entries and tombstones) to capacity allowed.
+    max_load_factor: f64,
+    /// Creates the hasher used for every key.
+    hash_builder: S,
+}
+
+/// The load factor used by `OpenHashTable::new`.
+const DEFAULT_MAX_LOAD_FACTOR: f64 = 0.7;
+
+/// The capacity used the first time an empty table grows.
+const MIN_SLOTS: usize = 8;
+
+/// Probe-length statistics for an `OpenHashTable`, returned by `OpenHashTable::stats`.
+#[derive(Debug)]
+struct ProbeStats {
+    len: usize,
+    bucket_count: usize,
+    tombstones: usize,
+    load_factor: f64,
+    /// `histogram[n]` is the number of entries found after exactly `n` probes.
+    histogram: Vec<usize>,
+    /// The most probes any lookup of a stored key needs.
+    longest_probe: usize,
+}
+
+impl fmt::Display for ProbeStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} entries and {} tombstones in {} slots, load factor {:.2}, longest probe {}",
+            self.len, self.tombstones, self.bucket_count, self.load_factor, self.longest_probe
+        )?;
+        // Probe lengths have a long tail, so group them into powers of two: 1, 2-3, 4-7, ...
+        write!(f, "  entries by probe length:")?;
+        let mut low = 1;
+        while low < self.histogram.len() {
+            let high = (low * 2).min(self.histogram.len());
+            let count: usize = self.histogram[low..high].iter().sum();
+            if count > 0 {
+                match high - low {
+                    1 => write!(f, " {}: {}", low, count)?,
+                    _ => write!(f, " {}-{}: {}", low, high - 1, count)?,
+                }
+            }
+            low *= 2;
+        }
+        Ok(())
+    }
 }
 
-impl HashTable {
-    fn new(size: usize) -> Self {
-        HashTable {
-            data: vec![None; size],
+/// A view into a single key of an `OpenHashTable`, which may or may not hold a value.
+/// Returned by `OpenHashTable::entry`.
+enum Entry<'a, K, V, S> {
+    Occupied(OccupiedEntry<'a, K, V, S>),
+    Vacant(VacantEntry<'a, K, V, S>),
+}
+
+/// An entry whose key is present in the table.
+struct OccupiedEntry<'a, K, V, S> {
+    table: &'a mut OpenHashTable<K, V, S>,
+    index: usize,
+}
+
+/// An entry whose key is absent from the table.
+struct VacantEntry<'a, K, V, S> {
+    table: &'a mut OpenHashTable<K, V, S>,
+    hash: u64,
+    key: K,
+}
+
+impl<K, V> OpenHashTable<K, V>
+where
+    K: Eq + Hash,
+{
+    /// Creates a new open-addressing hash table.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The initial number of slots, rounded up to a power of two. 0 is allowed.
+    /// * `probing` - The collision resolution strategy.
+    pub fn new(size: usize, probing: Probing) -> Self {
+        Self::with_load_factor(size, probing, DEFAULT_MAX_LOAD_FACTOR)
+    }
+
+    /// Creates a new table that grows once its load factor would exceed `max_load_factor`.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The initial number of slots, rounded up to a power of two.
+    /// * `probing` - The collision resolution strategy.
+    /// * `max_load_factor` - The largest allowed ratio of used slots to capacity, in `(0, 1)`.
+    pub fn with_load_factor(size: usize, probing: Probing, max_load_factor: f64) -> Self {
+        Self::with_load_factor_and_hasher(
+            size,
+            probing,
+            max_load_factor,
+            DefaultHashBuilder::default(),
+        )
+    }
+}
+
+impl<K, V, S> OpenHashTable<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Creates a new table that hashes its keys with hashers built by `hash_builder`.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The initial number of slots, rounded up to a power of two.
+    /// * `probing` - The collision resolution strategy.
+    /// * `hash_builder` - Creates the hasher used for every key.
+    pub fn with_hasher(size: usize, probing: Probing, hash_builder: S) -> Self {
+        Self::with_load_factor_and_hasher(size, probing, DEFAULT_MAX_LOAD_FACTOR, hash_builder)
+    }
+
+    /// Creates a new table with both a custom load factor and a custom hash function.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The initial number of slots, rounded up to a power of two.
+    /// * `probing` - The collision resolution strategy.
+    /// * `max_load_factor` - The largest allowed ratio of used slots to capacity, in `(0, 1)`.
+    /// * `hash_builder` - Creates the hasher used for every key.
+    pub fn with_load_factor_and_hasher(
+        size: usize,
+        probing: Probing,
+        max_load_factor: f64,
+        hash_builder: S,
+    ) -> Self {
+        assert!(
+            max_load_factor > 0.0 && max_load_factor < 1.0,
+            "the maximum load factor must be between 0 and 1"
+        );
+        OpenHashTable {
+            slots: Self::empty_slots(if size == 0 {
+                0
+            } else {
+                size.next_power_of_two()
+            }),
+            length: 0,
+            tombstones: 0,
+            probing,
+            max_load_factor,
+            hash_builder,
+        }
+    }
+
+    fn empty_slots(capacity: usize) -> Vec<Slot<K, V>> {
+        let mut slots = Vec::with_capacity(capacity);
+        slots.resize_with(capacity, || Slot::Empty);
+        slots
+    }
+
+    /// Returns the number of key-value pairs stored in the table.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the table holds no key-value pairs.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns the number of slots currently allocated.
+    pub fn bucket_count(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the current ratio of entries to slots.
+    pub fn load_factor(&self) -> f64 {
+        if self.slots.is_empty() {
+            0.0
+        } else {
+            self.length as f64 / self.slots.len() as f64
+        }
+    }
+
+    /// Returns the probing strategy of the table.
+    pub fn probing(&self) -> Probing {
+        self.probing
+    }
+
+    fn hash(&self, key: &K) -> u64 {
+        self.hash_builder.hash_one(key)
+    }
+
+    /// Returns the `i`-th slot of the probe sequence for `hash`.
+    fn probe(&self, hash: u64, i: usize) -> usize {
+        let mask = self.slots.len() - 1;
+        let home = hash as usize;
+        match self.probing {
+            Probing::Linear | Probing::RobinHood => home.wrapping_add(i) & mask,
+            Probing::Quadratic => home.wrapping_add(i * (i + 1) / 2) & mask,
+            Probing::DoubleHashing => {
+                // An odd step is coprime with the power-of-two capacity.
+                let step = (hash >> 32) as usize | 1;
+                home.wrapping_add(i.wrapping_mul(step)) & mask
+            }
+        }
+    }
+
+    /// Returns how far the entry in `index` sits from its home slot (Robin Hood only).
+    fn displacement(&self, hash: u64, index: usize) -> usize {
+        index.wrapping_sub(hash as usize) & (self.slots.len() - 1)
+    }
+
+    /// Finds the slot holding `key`, if any.
+    fn find(&self, hash: u64, key: &K) -> Option<usize> {
+        for i in 0..self.slots.len() {
+            let index = self.probe(hash, i);
+            match &self.slots[index] {
+                Slot::Empty => return None,
+                Slot::Tombstone => continue,
+                Slot::Occupied {
+                    hash: h, key: k, ..
+                } => {
+                    if *h == hash && k == key {
+                        return Some(index);
+                    }
+                    // Robin Hood keeps every run ordered by displacement, so once we pass an
+                    // entry that is closer to home than we would be, the key cannot be further on.
+                    if self.probing == Probing::RobinHood && self.displacement(*h, index) < i {
+                        return None;
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Grows or cleans the table if one more used slot would exceed the maximum load factor.
+    fn reserve_one(&mut self) {
+        let capacity = self.slots.len();
+        if (self.length + self.tombstones + 1) as f64 <= capacity as f64 * self.max_load_factor {
+            return;
+        }
+
+        // If tombstones are what fills the table, rebuilding at the same size is enough.
+        let new_capacity =
+            if (self.length + 1) as f64 > capacity as f64 * self.max_load_factor / 2.0 {
+                (capacity * 2).max(MIN_SLOTS)
+            } else {
+                capacity
+            };
+        self.rehash(new_capacity);
+    }
+
+    /// Moves every entry into a fresh slot array of `new_capacity` slots, dropping tombstones.
+    fn rehash(&mut self, new_capacity: usize) {
+        let old_slots = std::mem::replace(&mut self.slots, Self::empty_slots(new_capacity));
+        self.tombstones = 0;
+        for slot in old_slots {
+            if let Slot::Occupied { hash, key, value } = slot {
+                self.place(hash, key, value);
+            }
+        }
+    }
+
+    /// Stores a key that is known to be absent, and returns the slot it ends up in.
+    /// The table must have room for it.
+    fn place(&mut self, hash: u64, key: K, value: V) -> usize {
+        if self.probing == Probing::RobinHood {
+            return self.place_robin_hood(hash, key, value);
+        }
+
+        let mut i = 0;
+        loop {
+            let index = self.probe(hash, i);
+            match self.slots[index] {
+                Slot::Occupied { .. } => i += 1,
+                Slot::Tombstone | Slot::Empty => {
+                    if let Slot::Tombstone = self.slots[index] {
+                        self.tombstones -= 1;
+                    }
+                    self.slots[index] = Slot::Occupied { hash, key, value };
+                    return index;
+                }
+            }
+        }
+    }
+
+    /// Robin Hood insertion: whenever the entry being placed has travelled further than the
+    /// resident of a slot, they swap and the resident continues the search instead.
+    fn place_robin_hood(&mut self, hash: u64, key: K, value: V) -> usize {
+        let mask = self.slots.len() - 1;
+        let mut carried = Slot::Occupied { hash, key, value };
+        let mut index = hash as usize & mask;
+        let mut distance = 0;
+        let mut placed_at = None;
+
+        loop {
+            match &self.slots[index] {
+                Slot::Empty => {
+                    self.slots[index] = carried;
+                    return placed_at.unwrap_or(index);
+                }
+                Slot::Occupied { hash: h, .. } => {
+                    let resident_distance = self.displacement(*h, index);
+                    if resident_distance < distance {
+                        carried = std::mem::replace(&mut self.slots[index], carried);
+                        placed_at.get_or_insert(index);
+                        distance = resident_distance;
+                    }
+                }
+                Slot::Tombstone => unreachable!("Robin Hood tables never hold tombstones"),
+            }
+            index = (index + 1) & mask;
+            distance += 1;
+        }
+    }
+
+    /// Inserts a key-value pair into the table, replacing the value if the key already exists.
+    /// This operation runs in O(1) expected time while the load factor stays bounded.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to insert.
+    /// * `value` - The value associated with the key.
+    ///
+    /// # Returns
+    ///
+    /// The value previously associated with the key, or `None` if the key is new.
+    pub fn set(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Retrieves a reference to the value stored under `key`.
+    /// This operation runs in O(1) expected time.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        if self.slots.is_empty() {
+            return None;
+        }
+        match &self.slots[self.find(self.hash(key), key)?] {
+            Slot::Occupied { value, .. } => Some(value),
+            _ => None,
         }
     }
 
-    fn _hash(&self, key: &str) -> usize {
-        let mut hash = 0;
-        for (i, c) in key.chars().enumerate() {
-            hash = (hash + c as usize * i) % self.data.len();
+    /// Retrieves a mutable reference to the value stored under `key`.
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        if self.slots.is_empty() {
+            return None;
+        }
+        let index = self.find(self.hash(key), key)?;
+        match &mut self.slots[index] {
+            Slot::Occupied { value, .. } => Some(value),
+            _ => None,
         }
-        hash
     }
 
-    fn set(&mut self, key: String, value: i32) {
-        let index = self._hash(&key);
-        self.data[index] = Some((key, value));
+    /// Returns `true` if the table holds a value for `key`.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes `key` from the table.
+    ///
+    /// # Returns
+    ///
+    /// The value that was associated with the key, or `None` if the key does not exist.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        if self.slots.is_empty() {
+            return None;
+        }
+        let index = self.find(self.hash(key), key)?;
+        Some(self.remove_at(index).1)
     }
 
-    fn get(&self, key: &str) -> Option<i32> {
-        let index = self._hash(key);
-        if let Some((_, value)) = &self.data[index] {
-            Some(*value)
+    /// Removes the entry in slot `index`, which must be occupied.
+    fn remove_at(&mut self, index: usize) -> (K, V) {
+        let replacement = if self.probing == Probing::RobinHood {
+            Slot::Empty
         } else {
+            self.tombstones += 1;
+            Slot::Tombstone
+        };
+        let removed = std::mem::replace(&mut self.slots[index], replacement);
+        self.length -= 1;
+
+        if self.probing == Probing::RobinHood {
+            self.backward_shift(index);
+        }
+
+        match removed {
+            Slot::Occupied { key, value, .. } => (key, value),
+            _ => unreachable!("remove_at called on a free slot"),
+        }
+    }
+
+    /// Closes the gap at `hole` by moving each following displaced entry back one slot.
+    fn backward_shift(&mut self, mut hole: usize) {
+        let mask = self.slots.len() - 1;
+        loop {
+            let next = (hole + 1) & mask;
+            match &self.slots[next] {
+                Slot::Occupied { hash, .. } if self.displacement(*hash, next) > 0 => {
+                    self.slots.swap(hole, next);
+                    hole = next;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Gets the entry for the given key, for in-place inspection and modification.
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S> {
+        let hash = self.hash(&key);
+        let found = if self.slots.is_empty() {
             None
+        } else {
+            self.find(hash, &key)
+        };
+
+        match found {
+            Some(index) => Entry::Occupied(OccupiedEntry { table: self, index }),
+            None => Entry::Vacant(VacantEntry {
+                table: self,
+                hash,
+                key,
+            }),
+        }
+    }
+
+    /// Returns an iterator over all key-value pairs, in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.slots.iter().filter_map(|slot| match slot {
+            Slot::Occupied { key, value, .. } => Some((key, value)),
+            _ => None,
+        })
+    }
+
+    /// Returns an iterator over all keys stored in the table.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over all values stored in the table.
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Reports how many probes it takes to find each stored key.
+    /// Clusters from a weak hash function show up as a long tail in the histogram.
+    pub fn stats(&self) -> ProbeStats {
+        let probe_lengths: Vec<usize> = self
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| match slot {
+                Slot::Occupied { hash, .. } => {
+                    (0..self.slots.len()).position(|i| self.probe(*hash, i) == index)
+                }
+                _ => None,
+            })
+            .map(|i| i + 1)
+            .collect();
+
+        let longest_probe = probe_lengths.iter().copied().max().unwrap_or(0);
+        let mut histogram = vec![0; longest_probe + 1];
+        for &probes in &probe_lengths {
+            histogram[probes] += 1;
+        }
+
+        ProbeStats {
+            len: self.length,
+            bucket_count: self.slots.len(),
+            tombstones: self.tombstones,
+            load_factor: self.load_factor(),
+            histogram,
+            longest_probe,
+        }
+    }
+}
+
+impl<'a, K, V, S> Entry<'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Returns the key of this entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => &entry.key,
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant, and returns a mutable reference to the value.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the result of `default` if the entry is vacant, and returns a mutable reference to the value.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Inserts `V::default()` if the entry is vacant, and returns a mutable reference to the value.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Calls `f` on the value if the entry is occupied, and returns the entry for further chaining.
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K, V, S> OccupiedEntry<'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Returns the key of this entry.
+    pub fn key(&self) -> &K {
+        match &self.table.slots[self.index] {
+            Slot::Occupied { key, .. } => key,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns a reference to the value of this entry.
+    pub fn get(&self) -> &V {
+        match &self.table.slots[self.index] {
+            Slot::Occupied { value, .. } => value,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns a mutable reference to the value of this entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        match &mut self.table.slots[self.index] {
+            Slot::Occupied { value, .. } => value,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Converts the entry into a mutable reference to its value, tied to the table's lifetime.
+    pub fn into_mut(self) -> &'a mut V {
+        match &mut self.table.slots[self.index] {
+            Slot::Occupied { value, .. } => value,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Replaces the value of this entry and returns the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes this entry from the table and returns its key and value.
+    pub fn remove(self) -> (K, V) {
+        self.table.remove_at(self.index)
+    }
+}
+
+impl<'a, K, V, S> VacantEntry<'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Inserts a value under this entry's key and returns a mutable reference to it.
+    /// The table grows first if the insert would exceed the maximum load factor.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let table = self.table;
+        table.reserve_one();
+        let index = table.place(self.hash, self.key, value);
+        table.length += 1;
+
+        match &mut table.slots[index] {
+            Slot::Occupied { value, .. } => value,
+            _ => unreachable!(),
         }
     }
 }
 
 fn main() {
-    let mut my_hash_table = HashTable::new(50);
+    let mut my_hash_table = OpenHashTable::new(50, Probing::Linear);
     my_hash_table.set(String::from("grapes"), 10000);
-    println!("{:?}", my_hash_table.get("grapes")); // Output: Some(10000)
+    println!("{:?}", my_hash_table.get(&String::from("grapes"))); // Output: Some(10000)
     my_hash_table.set(String::from("apples"), 9);
-    println!("{:?}", my_hash_table.get("apples")); // Output: Some(9)
+    println!("{:?}", my_hash_table.get(&String::from("apples"))); // Output: Some(9)
+
+    // A rough benchmark: the same workload under each probing strategy.
+    for probing in [
+        Probing::Linear,
+        Probing::Quadratic,
+        Probing::DoubleHashing,
+        Probing::RobinHood,
+    ] {
+        let start = std::time::Instant::now();
+        let mut table = OpenHashTable::new(0, probing);
+        for i in 0..100_000u64 {
+            table.set(i, i * 2);
+        }
+        for i in (0..100_000u64).step_by(2) {
+            table.remove(&i);
+        }
+        *table.entry(1).or_default() += 1;
+        table.entry(2).and_modify(|v| *v += 1).or_insert(4);
+        if let Entry::Occupied(entry) = table.entry(3) {
+            if *entry.get() == 6 {
+                entry.remove();
+            }
+        }
+        let hits = (0..100_000u64).filter(|i| table.contains_key(i)).count();
+        if let Some(value) = table.get_mut(&5) {
+            *value = 0;
+        }
+
+        println!(
+            "{:?}: {} hits, {} entries in {} slots (load {:.2}), sum {}, {} keys, empty: {}, {:?}",
+            table.probing(),
+            hits,
+            table.len(),
+            table.bucket_count(),
+            table.load_factor(),
+            table.values().sum::<u64>(),
+            table.keys().count(),
+            table.is_empty(),
+            start.elapsed()
+        );
+    }
+
+    let mut table = OpenHashTable::with_load_factor(4, Probing::RobinHood, 0.9);
+    let text = "the quick brown fox jumps over the lazy dog the end";
+    for word in text.split_whitespace() {
+        *table.entry(word).or_insert(0) += 1;
+    }
+    let the = table.entry("the");
+    println!("Counted '{}'", the.key());
+    for (word, count) in table.iter() {
+        println!("{}: {}", word, count);
+    }
+
+    // Each hash function under each probing strategy, on the same keys.
+    let keys: Vec<String> = (0..5000).map(|i| format!("user-{}", i)).collect();
+    fn report<S: BuildHasher>(name: &str, probing: Probing, keys: &[String], hash_builder: S) {
+        let mut table = OpenHashTable::with_hasher(0, probing, hash_builder);
+        for key in keys {
+            table.set(key.as_str(), ());
+        }
+        let stats = table.stats();
+        println!(
+            "{} / {:?}: longest probe {}",
+            name, probing, stats.longest_probe
+        );
+        if stats.longest_probe > 64 {
+            println!("{}", stats);
+        }
+    }
+    for probing in [Probing::Linear, Probing::RobinHood] {
+        report(
+            "DefaultHasher",
+            probing,
+            &keys,
+            DefaultHashBuilder::default(),
+        );
+        report("FNV-1a", probing, &keys, BuildFnvHasher::default());
+        report(
+            "SipHash-2-4, fixed key",
+            probing,
+            &keys,
+            SeededSipHash::new(1, 2),
+        );
+        report(
+            "SipHash-2-4, random key",
+            probing,
+            &keys,
+            SeededSipHash::random(),
+        );
+        report(
+            "Polynomial",
+            probing,
+            &keys,
+            BuildPolynomialHasher::default(),
+        );
+    }
+    println!(
+        "{}",
+        OpenHashTable::<&str, ()>::new(4, Probing::Quadratic).stats()
+    );
 }
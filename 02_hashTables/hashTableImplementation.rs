@@ -1,53 +1,421 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::time::{Duration, Instant};
+
+#[allow(dead_code)]
+#[path = "hashers.rs"]
+pub mod hashers;
+
+use hashers::{BuildFnvHasher, BuildPolynomialHasher, DefaultHashBuilder, SeededSipHash};
+
 /// A simple hash table implementation in Rust.
 ///
 /// This hash table uses separate chaining to handle hash collisions.
 /// Each bucket at a given index in the vector can hold multiple key-value pairs that share the same hash.
-struct HashTable<K, V> {
-    /// The internal storage for the hash table, where each position can store a vector of tuples (key, value).
-    data: Vec<Option<Vec<(K, V)>>>,
+///
+/// The table keeps track of how many entries it holds and doubles its bucket count whenever the
+/// load factor (entries per bucket) would exceed the configured maximum, so chains stay short.
+///
+/// The hash function is pluggable through the `S: BuildHasher` parameter, so a table can use
+/// std's default hasher, one of the hashers in hashers.rs, or any other.
+///
+/// By default growing moves every entry at once, which is an O(n) pause. A table built with
+/// `with_incremental_rehash` instead keeps the old bucket array around after growing and moves a
+/// few of its buckets on every later insert, update or removal, so no single operation pays for
+/// the whole move. Until the move is finished, lookups check both bucket arrays.
+///
+/// Like std's `HashMap`, lookups and removals accept any borrowed form of the key, so a table
+/// with `String` keys can be searched with a `&str`.
+pub struct HashTable<K, V, S = DefaultHashBuilder> {
+    /// The internal storage for the hash table, where each bucket stores a vector of tuples (key, value).
+    data: Vec<Vec<(K, V)>>,
+    /// The bucket array being emptied into `data` by an incremental rehash, or empty if none is running.
+    old_data: Vec<Vec<(K, V)>>,
+    /// The bucket array an incremental rehash is setting up, a few empty buckets per operation,
+    /// before it takes the place of `data`.
+    next_data: Vec<Vec<(K, V)>>,
+    /// The number of buckets `next_data` will hold once it is ready, or 0 if no resize is pending.
+    next_size: usize,
+    /// The index of the next bucket in `old_data` to migrate.
+    rehash_cursor: usize,
+    /// The number of entries still waiting in `old_data`.
+    old_length: usize,
+    /// How many old buckets each operation migrates, or `None` to rehash all at once.
+    rehash_step: Option<usize>,
+    /// The number of key-value pairs stored in the table.
+    length: usize,
+    /// The largest ratio of entries to buckets allowed before the table grows.
+    max_load_factor: f64,
+    /// Creates the hasher used for every key.
+    hash_builder: S,
+}
+
+/// The load factor used by `HashTable::new`.
+const DEFAULT_MAX_LOAD_FACTOR: f64 = 0.75;
+
+/// The bucket count used the first time an empty table grows.
+const MIN_BUCKETS: usize = 8;
+
+/// How many empty buckets an incremental rehash sets up for every old bucket it migrates.
+/// Setting up an empty bucket is far cheaper than migrating one, so this keeps the pending
+/// resize short without adding noticeably to any single operation.
+const NEW_BUCKETS_PER_MIGRATED_BUCKET: usize = 8;
+
+/// How far an incremental rehash has got, returned by `HashTable::rehash_progress`.
+#[derive(Debug)]
+pub struct RehashProgress {
+    /// Old buckets already migrated (or found empty).
+    migrated_buckets: usize,
+    /// Old buckets in total.
+    total_buckets: usize,
+    /// Entries still stored in the old buckets.
+    remaining_entries: usize,
+}
+
+impl fmt::Display for RehashProgress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} old buckets migrated ({:.0}%), {} entries left",
+            self.migrated_buckets,
+            self.total_buckets,
+            100.0 * self.migrated_buckets as f64 / self.total_buckets as f64,
+            self.remaining_entries
+        )
+    }
+}
+
+/// Collision statistics for a `HashTable`, returned by `HashTable::stats`.
+#[derive(Debug)]
+pub struct TableStats {
+    len: usize,
+    bucket_count: usize,
+    load_factor: f64,
+    /// `histogram[n]` is the number of buckets holding exactly `n` entries.
+    histogram: Vec<usize>,
+    /// The number of entries in the fullest bucket.
+    longest_chain: usize,
+}
+
+impl fmt::Display for TableStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} entries in {} buckets, load factor {:.2}, longest chain {}",
+            self.len, self.bucket_count, self.load_factor, self.longest_chain
+        )?;
+        write!(f, "  buckets by chain length:")?;
+        for (length, &count) in self.histogram.iter().enumerate() {
+            if count > 0 {
+                write!(f, " {}: {}", length, count)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A view into a single key of a `HashTable`, which may or may not hold a value.
+/// Returned by `HashTable::entry`.
+pub enum Entry<'a, K, V, S> {
+    Occupied(OccupiedEntry<'a, K, V, S>),
+    Vacant(VacantEntry<'a, K, V, S>),
+}
+
+/// An entry whose key is present in the table.
+pub struct OccupiedEntry<'a, K, V, S> {
+    table: &'a mut HashTable<K, V, S>,
+    bucket: usize,
+    slot: usize,
+}
+
+/// An entry whose key is absent from the table.
+pub struct VacantEntry<'a, K, V, S> {
+    table: &'a mut HashTable<K, V, S>,
+    key: K,
 }
 
 impl<K, V> HashTable<K, V>
 where
-    K: Eq + std::hash::Hash + Clone,
-    V: Clone,
+    K: Eq + Hash,
 {
     /// Creates a new hash table with the specified size.
     ///
     /// # Arguments
     ///
-    /// * `size` - The number of buckets in the hash table.
+    /// * `size` - The initial number of buckets in the hash table. A size of 0 is allowed;
+    ///   buckets are then allocated on the first insert.
     pub fn new(size: usize) -> Self {
+        Self::with_load_factor(size, DEFAULT_MAX_LOAD_FACTOR)
+    }
+
+    /// Creates a new hash table that grows once its load factor would exceed `max_load_factor`.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The initial number of buckets in the hash table.
+    /// * `max_load_factor` - The largest allowed ratio of entries to buckets. Must be positive
+    ///   and finite.
+    pub fn with_load_factor(size: usize, max_load_factor: f64) -> Self {
+        Self::with_load_factor_and_hasher(size, max_load_factor, DefaultHashBuilder::default())
+    }
+}
+
+impl<K, V, S> HashTable<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Creates a new hash table that hashes its keys with hashers built by `hash_builder`.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The initial number of buckets in the hash table.
+    /// * `hash_builder` - Creates the hasher used for every key.
+    pub fn with_hasher(size: usize, hash_builder: S) -> Self {
+        Self::with_load_factor_and_hasher(size, DEFAULT_MAX_LOAD_FACTOR, hash_builder)
+    }
+
+    /// Creates a new hash table with both a custom load factor and a custom hash function.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The initial number of buckets in the hash table.
+    /// * `max_load_factor` - The largest allowed ratio of entries to buckets. Must be positive
+    ///   and finite.
+    /// * `hash_builder` - Creates the hasher used for every key.
+    pub fn with_load_factor_and_hasher(size: usize, max_load_factor: f64, hash_builder: S) -> Self {
+        assert!(
+            max_load_factor > 0.0 && max_load_factor.is_finite(),
+            "the maximum load factor must be positive and finite"
+        );
+        let mut data = Vec::with_capacity(size);
+        data.resize_with(size, Vec::new);
         HashTable {
-            data: vec![None; size],
+            data,
+            length: 0,
+            old_data: Vec::new(),
+            next_data: Vec::new(),
+            next_size: 0,
+            rehash_cursor: 0,
+            old_length: 0,
+            rehash_step: None,
+            max_load_factor,
+            hash_builder,
+        }
+    }
+
+    /// Switches the table to incremental rehashing: when it grows, the entries are moved over
+    /// the following operations instead of all at once.
+    ///
+    /// Every `set`, `get_mut`, `remove` and `entry` call migrates up to `buckets_per_step` old
+    /// buckets, plus the one bucket its own key hashes to. A resize finishes before the next
+    /// one is due as long as `buckets_per_step` is at least `1.25 / max_load_factor` (2 for
+    /// the default); if it is not, the next resize first moves whatever is left in one go.
+    /// The new, empty bucket array is set up the same way, `8 * buckets_per_step` buckets per
+    /// operation, and the migration starts once it is complete; until then the table keeps
+    /// using its current buckets and may briefly run above its maximum load factor.
+    ///
+    /// # Arguments
+    ///
+    /// * `buckets_per_step` - The number of old buckets each operation migrates. Must be positive.
+    pub fn with_incremental_rehash(mut self, buckets_per_step: usize) -> Self {
+        assert!(
+            buckets_per_step > 0,
+            "an incremental rehash must migrate at least one bucket per step"
+        );
+        self.rehash_step = Some(buckets_per_step);
+        self
+    }
+
+    /// Returns the number of key-value pairs stored in the hash table.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the hash table holds no key-value pairs.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns the number of buckets currently allocated.
+    pub fn bucket_count(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the current ratio of entries to buckets.
+    pub fn load_factor(&self) -> f64 {
+        if self.data.is_empty() {
+            0.0
+        } else {
+            self.length as f64 / self.data.len() as f64
         }
     }
 
     /// Generates a hash index based on the key to determine where to store the data.
     /// Typically, this method runs in O(1) average time complexity.
+    /// The table must have at least one bucket.
     ///
     /// # Arguments
     ///
     /// * `key` - The key used to calculate the hash.
-    fn hash(&self, key: &K) -> usize {
-        use std::hash::{Hash, Hasher};
-        let mut hasher = std::collections::hash_map::DefaultHasher::new();
-        key.hash(&mut hasher);
-        (hasher.finish() as usize) % self.data.len()
+    fn hash<Q: Hash + ?Sized>(&self, key: &Q) -> usize {
+        (self.hash_builder.hash_one(key) as usize) % self.data.len()
+    }
+
+    /// Finds the bucket and the position within that bucket where `key` is stored.
+    /// Only `data` is searched; entries still waiting in `old_data` are not found.
+    fn find<Q>(&self, key: &Q) -> Option<(usize, usize)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        if self.data.is_empty() {
+            return None;
+        }
+        let index = self.hash(key);
+        self.data[index]
+            .iter()
+            .position(|(k, _)| k.borrow() == key)
+            .map(|slot| (index, slot))
+    }
+
+    /// Finds the entry for `key` in either bucket array.
+    fn find_anywhere<Q>(&self, key: &Q) -> Option<&(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        if let Some((index, slot)) = self.find(key) {
+            return Some(&self.data[index][slot]);
+        }
+        if self.old_data.is_empty() {
+            return None;
+        }
+        let index = (self.hash_builder.hash_one(key) as usize) % self.old_data.len();
+        self.old_data[index].iter().find(|(k, _)| k.borrow() == key)
+    }
+
+    /// Returns how far the running incremental rehash has got, or `None` if there is none.
+    pub fn rehash_progress(&self) -> Option<RehashProgress> {
+        if self.old_data.is_empty() {
+            return None;
+        }
+        Some(RehashProgress {
+            migrated_buckets: self.rehash_cursor,
+            total_buckets: self.old_data.len(),
+            remaining_entries: self.old_length,
+        })
+    }
+
+    /// Moves every entry of the old bucket at `index` into `data`.
+    fn migrate_bucket(&mut self, index: usize) {
+        let bucket = std::mem::take(&mut self.old_data[index]);
+        self.old_length -= bucket.len();
+        for (key, value) in bucket {
+            let new_index = self.hash(&key);
+            self.data[new_index].push((key, value));
+        }
+    }
+
+    /// Does one step of a running incremental rehash, and makes sure `key` is no longer in
+    /// `old_data`, so that `find` alone can be trusted for it afterwards.
+    /// This operation runs in O(1) average time for a fixed step size.
+    fn migrate_step<Q: Hash + ?Sized>(&mut self, key: &Q) {
+        let Some(step) = self.rehash_step else {
+            return;
+        };
+        if self.next_size > 0 {
+            let end =
+                (self.next_data.len() + step * NEW_BUCKETS_PER_MIGRATED_BUCKET).min(self.next_size);
+            self.next_data.resize_with(end, Vec::new);
+            if end == self.next_size {
+                self.start_rehash();
+            }
+        }
+        if self.old_data.is_empty() {
+            return;
+        }
+
+        let end = (self.rehash_cursor + step).min(self.old_data.len());
+        for index in self.rehash_cursor..end {
+            self.migrate_bucket(index);
+        }
+        self.rehash_cursor = end;
+
+        if self.old_length == 0 {
+            self.finish_rehash();
+        } else {
+            // Buckets behind the cursor are already empty, so this only does work ahead of it.
+            let index = (self.hash_builder.hash_one(key) as usize) % self.old_data.len();
+            self.migrate_bucket(index);
+        }
+    }
+
+    /// Migrates everything still left in `old_data` and drops it.
+    fn finish_rehash(&mut self) {
+        for index in self.rehash_cursor..self.old_data.len() {
+            self.migrate_bucket(index);
+        }
+        self.old_data = Vec::new();
+        self.rehash_cursor = 0;
+    }
+
+    /// Swaps the fully set up `next_data` in for `data` and starts migrating into it.
+    fn start_rehash(&mut self) {
+        self.finish_rehash();
+        self.old_data = std::mem::replace(&mut self.data, std::mem::take(&mut self.next_data));
+        self.old_length = self.length;
+        self.next_size = 0;
+    }
+
+    /// Grows the table if adding one more entry would exceed the maximum load factor.
+    fn reserve_one(&mut self) {
+        if (self.length + 1) as f64 > self.data.len() as f64 * self.max_load_factor {
+            let new_size = (self.data.len() * 2).max(MIN_BUCKETS);
+            if self.data.is_empty() || self.rehash_step.is_none() {
+                // An empty table has nowhere to put the entry, and there is nothing to move.
+                self.rehash(new_size);
+            } else if self.next_size == 0 {
+                // Only reserve the memory here; `migrate_step` fills it in bit by bit.
+                self.next_data = Vec::with_capacity(new_size);
+                self.next_size = new_size;
+            }
+        }
+    }
+
+    /// Moves every entry into a fresh set of `new_size` buckets.
+    /// This operation runs in O(n) time, but happens rarely enough that inserts stay O(1) amortized.
+    fn rehash(&mut self, new_size: usize) {
+        let mut new_data = Vec::with_capacity(new_size);
+        new_data.resize_with(new_size, Vec::new);
+        let old_data = std::mem::replace(&mut self.data, new_data);
+
+        for (key, value) in old_data.into_iter().flatten() {
+            let index = self.hash(&key);
+            self.data[index].push((key, value));
+        }
     }
 
-    /// Inserts a key-value pair into the hash table.
-    /// This operation runs in O(1) average time complexity because it appends the element at the end of a vector.
+    /// Inserts a key-value pair into the hash table, replacing the value if the key already exists.
+    /// This operation runs in O(1) amortized time: it searches one short bucket, and occasionally
+    /// grows the table.
     ///
     /// # Arguments
     ///
     /// * `key` - The key to insert into the hash table.
     /// * `value` - The value associated with the key.
-    pub fn set(&mut self, key: K, value: V) {
-        let index = self.hash(&key);
-        match self.data[index].as_mut() {
-            Some(bucket) => bucket.push((key, value)),
-            None => self.data[index] = Some(vec![(key, value)]),
+    ///
+    /// # Returns
+    ///
+    /// The value previously associated with the key, or `None` if the key is new.
+    pub fn set(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
         }
     }
 
@@ -60,36 +428,254 @@ where
     ///
     /// # Returns
     ///
-    /// Returns an option containing the value if the key exists, or `None` if the key does not exist.
-    pub fn get(&self, key: &K) -> Option<V> {
-        let index = self.hash(key);
-        self.data[index].as_ref().and_then(|bucket| {
-            bucket
-                .iter()
-                .find(|(k, _)| k == key)
-                .map(|(_, v)| v.clone())
-        })
+    /// Returns an option containing a reference to the value if the key exists, or `None` if the key does not exist.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find_anywhere(key).map(|(_, value)| value)
+    }
+
+    /// Retrieves a mutable reference to the value stored under the given key.
+    /// This method runs in O(1) average time complexity.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.migrate_step(key);
+        let (index, slot) = self.find(key)?;
+        Some(&mut self.data[index][slot].1)
+    }
+
+    /// Returns `true` if the hash table holds a value for the given key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find_anywhere(key).is_some()
     }
 
-    /// Returns a vector of all keys stored in the hash table.
-    /// This operation has O(n) time complexity, where n is the number of keys stored in the hash table.
+    /// Removes a key from the hash table.
+    /// This method runs in O(1) average time complexity.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to remove.
     ///
     /// # Returns
     ///
-    /// A vector containing all keys.
-    pub fn keys(&self) -> Vec<K> {
-        let mut keys = Vec::new();
-        for bucket in self.data.iter().filter_map(|b| b.as_ref()) {
-            for (key, _) in bucket {
-                keys.push(key.clone());
-            }
+    /// The value that was associated with the key, or `None` if the key does not exist.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.migrate_step(key);
+        let (index, slot) = self.find(key)?;
+        self.length -= 1;
+        Some(self.data[index].swap_remove(slot).1)
+    }
+
+    /// Gets the entry for the given key, for in-place inspection and modification.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut counts = HashTable::new(16);
+    /// for word in ["a", "b", "a"] {
+    ///     *counts.entry(word).or_insert(0) += 1;
+    /// }
+    /// assert_eq!(counts.get(&"a"), Some(&2));
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S> {
+        self.migrate_step(&key);
+        match self.find(&key) {
+            Some((bucket, slot)) => Entry::Occupied(OccupiedEntry {
+                table: self,
+                bucket,
+                slot,
+            }),
+            None => Entry::Vacant(VacantEntry { table: self, key }),
+        }
+    }
+
+    /// Keeps only the entries for which `keep` returns `true`. `keep` may modify the values.
+    /// This operation runs in O(n + b) time, where b is the number of buckets.
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut keep: F) {
+        for bucket in &mut self.data {
+            bucket.retain_mut(|(key, value)| keep(key, value));
+        }
+        for bucket in &mut self.old_data {
+            bucket.retain_mut(|(key, value)| keep(key, value));
+        }
+        self.old_length = self.old_data.iter().map(Vec::len).sum();
+        self.length = self.data.iter().map(Vec::len).sum::<usize>() + self.old_length;
+    }
+
+    /// Returns an iterator over all key-value pairs, in no particular order.
+    /// Walking the whole table takes O(n + b) time, where b is the number of buckets.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.data
+            .iter()
+            .chain(&self.old_data)
+            .flatten()
+            .map(|(key, value)| (key, value))
+    }
+
+    /// Returns an iterator over all keys stored in the hash table.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over all values stored in the hash table.
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Reports how evenly the keys are spread over the buckets.
+    /// A good hash function on a table at load factor 0.75 rarely produces chains longer than
+    /// four or five; a long tail in the histogram points to a weak hash for the data.
+    /// During an incremental rehash the histogram also covers the old buckets.
+    pub fn stats(&self) -> TableStats {
+        let buckets = || self.data.iter().chain(&self.old_data);
+        let longest_chain = buckets().map(Vec::len).max().unwrap_or(0);
+        let mut histogram = vec![0; longest_chain + 1];
+        for bucket in buckets() {
+            histogram[bucket.len()] += 1;
+        }
+
+        TableStats {
+            len: self.length,
+            bucket_count: self.data.len(),
+            load_factor: self.load_factor(),
+            histogram,
+            longest_chain,
+        }
+    }
+}
+
+/// The iterator returned by `HashTable::into_iter`.
+type IntoIter<K, V> =
+    std::iter::Flatten<std::iter::Chain<BucketsIntoIter<K, V>, BucketsIntoIter<K, V>>>;
+
+/// An owning iterator over one bucket array.
+type BucketsIntoIter<K, V> = std::vec::IntoIter<Vec<(K, V)>>;
+
+impl<K, V, S> IntoIterator for HashTable<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    /// Consumes the table and yields every key-value pair, in no particular order.
+    fn into_iter(self) -> IntoIter<K, V> {
+        self.data.into_iter().chain(self.old_data).flatten()
+    }
+}
+
+impl<'a, K, V, S> Entry<'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Returns the key of this entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => &entry.key,
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant, and returns a mutable reference to the value.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the result of `default` if the entry is vacant, and returns a mutable reference to the value.
+    /// `default` is only called when the key is missing.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Inserts `V::default()` if the entry is vacant, and returns a mutable reference to the value.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Calls `f` on the value if the entry is occupied, and returns the entry for further chaining.
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
         }
-        keys
+        self
+    }
+}
+
+impl<'a, K, V, S> OccupiedEntry<'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Returns the key of this entry.
+    pub fn key(&self) -> &K {
+        &self.table.data[self.bucket][self.slot].0
+    }
+
+    /// Returns a reference to the value of this entry.
+    pub fn get(&self) -> &V {
+        &self.table.data[self.bucket][self.slot].1
+    }
+
+    /// Returns a mutable reference to the value of this entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.table.data[self.bucket][self.slot].1
+    }
+
+    /// Converts the entry into a mutable reference to its value, tied to the table's lifetime.
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.table.data[self.bucket][self.slot].1
+    }
+
+    /// Replaces the value of this entry and returns the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes this entry from the table and returns its key and value.
+    pub fn remove(self) -> (K, V) {
+        self.table.length -= 1;
+        self.table.data[self.bucket].swap_remove(self.slot)
+    }
+}
+
+impl<'a, K, V, S> VacantEntry<'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Inserts a value under this entry's key and returns a mutable reference to it.
+    /// The table grows first if the insert would exceed the maximum load factor.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let table = self.table;
+        table.reserve_one();
+        let index = table.hash(&self.key);
+        table.length += 1;
+
+        let bucket = &mut table.data[index];
+        bucket.push((self.key, value));
+        &mut bucket.last_mut().unwrap().1
     }
 }
 
 fn main() {
-    let mut my_hash_table = HashTable::new(50);
+    let mut my_hash_table = HashTable::new(0);
     my_hash_table.set("grapes", 10000);
     my_hash_table.set("apples", 54);
     my_hash_table.set("oranges", 2);
@@ -98,6 +684,111 @@ fn main() {
         println!("Value for 'grapes': {}", value);
     }
 
-    let keys = my_hash_table.keys();
+    // Setting an existing key replaces its value instead of adding a duplicate.
+    println!(
+        "Old value for 'grapes': {:?}",
+        my_hash_table.set("grapes", 5)
+    );
+    if let Some(value) = my_hash_table.get_mut(&"apples") {
+        *value += 1;
+    }
+    println!("Removed 'oranges': {:?}", my_hash_table.remove(&"oranges"));
+    println!(
+        "Contains 'oranges': {}",
+        my_hash_table.contains_key(&"oranges")
+    );
+
+    let keys: Vec<_> = my_hash_table.keys().collect();
     println!("Keys in my hash table: {:?}", keys);
+    println!(
+        "Total fruit: {}",
+        my_hash_table.values().copied().sum::<i32>()
+    );
+
+    // The entry API makes counting a one-liner, and the table grows as words are added.
+    let mut word_counts = HashTable::with_load_factor(2, 1.0);
+    let text = "the quick brown fox jumps over the lazy dog the end";
+    for word in text.split_whitespace() {
+        word_counts
+            .entry(word)
+            .and_modify(|count| *count += 1)
+            .or_insert(1);
+    }
+    *word_counts.entry("fox").or_default() += 10;
+    let the = word_counts.entry("the");
+    println!("'{}' is the most common word", the.key());
+    for (word, count) in word_counts.iter().filter(|(_, &count)| count > 1) {
+        println!("{}: {}", word, count);
+    }
+    if let Entry::Occupied(entry) = word_counts.entry("end") {
+        if *entry.get() == 1 {
+            println!("Removed entry: {:?}", entry.remove());
+        }
+    }
+    println!(
+        "{} words in {} buckets (load factor {:.2}), empty: {}",
+        word_counts.len(),
+        word_counts.bucket_count(),
+        word_counts.load_factor(),
+        word_counts.is_empty()
+    );
+
+    // The same keys under different hash functions. Each table has already grown to keep its
+    // load factor under 0.75, so any long chains are the hash function's fault.
+    let keys: Vec<String> = (0..5000).map(|i| format!("user-{}", i)).collect();
+    fn report<S: BuildHasher>(name: &str, keys: &[String], hash_builder: S) {
+        let mut table = HashTable::with_hasher(0, hash_builder);
+        for key in keys {
+            table.set(key.as_str(), ());
+        }
+        let stats = table.stats();
+        println!("{}: longest chain {}", name, stats.longest_chain);
+        if stats.longest_chain > 8 {
+            println!("{}", stats);
+        }
+    }
+    report("DefaultHasher", &keys, DefaultHashBuilder::default());
+    report("FNV-1a", &keys, BuildFnvHasher::default());
+    report("SipHash-2-4, fixed key", &keys, SeededSipHash::new(1, 2));
+    report("SipHash-2-4, random key", &keys, SeededSipHash::random());
+    report("Polynomial", &keys, BuildPolynomialHasher::default());
+    println!("{}", HashTable::<&str, ()>::new(4).stats());
+
+    // An incremental table spreads each resize over the inserts that follow it.
+    let mut sessions = HashTable::new(0).with_incremental_rehash(4);
+    for i in 0..1000 {
+        sessions.set(i, i * 2);
+        if let Some(progress) = sessions.rehash_progress() {
+            if i % 200 == 0 {
+                println!("After inserting {}: {}", i, progress);
+            }
+        }
+    }
+    // Keys still in the old buckets are found all the same.
+    let found = (0..1000)
+        .filter(|i| sessions.get(i) == Some(&(i * 2)))
+        .count();
+    println!("Found {} of 1000 keys", found);
+
+    // The slowest single insert, with and without incremental rehashing. Both tables stay
+    // alive until the end, so freeing the first one cannot slow down the second.
+    fn slowest_insert(table: &mut HashTable<u64, u64>) -> Duration {
+        let mut slowest = Duration::ZERO;
+        for i in 0..1_000_000 {
+            let start = Instant::now();
+            table.set(i, i);
+            slowest = slowest.max(start.elapsed());
+        }
+        slowest
+    }
+    let mut at_once = HashTable::new(0);
+    let mut incremental = HashTable::new(0).with_incremental_rehash(2);
+    println!(
+        "Slowest insert, rehashing at once: {:?}",
+        slowest_insert(&mut at_once)
+    );
+    println!(
+        "Slowest insert, rehashing incrementally: {:?}",
+        slowest_insert(&mut incremental)
+    );
 }
@@ -0,0 +1,198 @@
+/*
+  The hash functions shared by the hash tables and sketches in this directory. A standalone
+  example pulls them in with
+
+    #[path = "hashers.rs"]
+    mod hashers;
+
+  - FnvHasher: FNV-1a, fast and stable across runs and Rust releases, but easy to attack.
+  - PolynomialHasher: a deliberately weak hash, for showing what collisions do to a table.
+  - SipHasher24: keyed SipHash-2-4, whose output is fixed by its specification; SeededSipHash
+    builds them under one key, chosen by the caller or drawn at random.
+
+  `mix` is not a hasher but a finalizer, for spreading a weak hash over all 64 bits.
+*/
+
+use std::collections::hash_map::{DefaultHasher, RandomState};
+use std::hash::{BuildHasher, BuildHasherDefault, Hasher};
+
+/// The hasher used when no other is given: std's SipHash-1-3 with fixed keys.
+pub type DefaultHashBuilder = BuildHasherDefault<DefaultHasher>;
+
+/// The 64-bit FNV-1a hash: XOR in each byte, then multiply by the FNV prime.
+/// Very fast for short keys, but trivial to attack with crafted input.
+pub struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        FnvHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Builds `FnvHasher`s.
+pub type BuildFnvHasher = BuildHasherDefault<FnvHasher>;
+
+/// The positional string hash from the original hashTableExercise.rs: the sum of every byte
+/// multiplied by its position. Anagrams collide and the first byte is ignored entirely, which
+/// makes it a handy example of a bad hash function.
+#[derive(Default)]
+pub struct PolynomialHasher {
+    hash: u64,
+    position: u64,
+}
+
+impl Hasher for PolynomialHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.hash = self.hash.wrapping_add(byte as u64 * self.position);
+            self.position += 1;
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// Builds `PolynomialHasher`s.
+pub type BuildPolynomialHasher = BuildHasherDefault<PolynomialHasher>;
+
+/// SipHash-2-4 with a caller-chosen 128-bit key. Without the key, an attacker cannot predict
+/// which inputs collide, so crafted keys cannot force the table into long chains.
+#[derive(Clone)]
+pub struct SipHasher24 {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+    /// Input bytes not yet compressed, packed little-endian.
+    tail: u64,
+    tail_length: usize,
+    length: usize,
+}
+
+impl SipHasher24 {
+    pub fn with_keys(k0: u64, k1: u64) -> Self {
+        SipHasher24 {
+            v0: k0 ^ 0x736f_6d65_7073_6575,
+            v1: k1 ^ 0x646f_7261_6e64_6f6d,
+            v2: k0 ^ 0x6c79_6765_6e65_7261,
+            v3: k1 ^ 0x7465_6462_7974_6573,
+            tail: 0,
+            tail_length: 0,
+            length: 0,
+        }
+    }
+
+    fn round(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13) ^ self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16) ^ self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21) ^ self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17) ^ self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    fn compress(&mut self, word: u64) {
+        self.v3 ^= word;
+        self.round();
+        self.round();
+        self.v0 ^= word;
+    }
+}
+
+impl Hasher for SipHasher24 {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.tail |= (byte as u64) << (8 * self.tail_length);
+            self.tail_length += 1;
+            if self.tail_length == 8 {
+                self.compress(self.tail);
+                self.tail = 0;
+                self.tail_length = 0;
+            }
+        }
+        self.length += bytes.len();
+    }
+
+    fn finish(&self) -> u64 {
+        let mut state = self.clone();
+        state.compress(((self.length as u64 & 0xff) << 56) | self.tail);
+        state.v2 ^= 0xff;
+        for _ in 0..4 {
+            state.round();
+        }
+        state.v0 ^ state.v1 ^ state.v2 ^ state.v3
+    }
+}
+
+/// Builds `SipHasher24`s that all share one key.
+#[derive(Clone, PartialEq)]
+pub struct SeededSipHash {
+    k0: u64,
+    k1: u64,
+}
+
+impl SeededSipHash {
+    /// Uses the given 128-bit key, for hashes that are reproducible across runs.
+    pub fn new(k0: u64, k1: u64) -> Self {
+        SeededSipHash { k0, k1 }
+    }
+
+    /// Draws a fresh key from the operating system's randomness, via std's `RandomState`.
+    pub fn random() -> Self {
+        let state = RandomState::new();
+        SeededSipHash::new(state.hash_one(0u8), state.hash_one(1u8))
+    }
+
+    /// Returns the first half of the key, for storing it alongside data hashed with it.
+    pub fn k0(&self) -> u64 {
+        self.k0
+    }
+
+    /// Returns the second half of the key.
+    pub fn k1(&self) -> u64 {
+        self.k1
+    }
+}
+
+impl Default for SeededSipHash {
+    /// A fixed key, for hashes that agree between runs without choosing a key.
+    fn default() -> Self {
+        SeededSipHash::new(0x5eed_0f5c_e7c4_e5a1, 0x0123_4567_89ab_cdef)
+    }
+}
+
+impl BuildHasher for SeededSipHash {
+    type Hasher = SipHasher24;
+
+    fn build_hasher(&self) -> SipHasher24 {
+        SipHasher24::with_keys(self.k0, self.k1)
+    }
+}
+
+/// Spreads the bits of a hash over the whole word (the SplitMix64 finalizer).
+pub fn mix(mut hash: u64) -> u64 {
+    hash ^= hash >> 30;
+    hash = hash.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash ^= hash >> 27;
+    hash = hash.wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
@@ -0,0 +1,491 @@
+/*
+  A small embedded key-value store: the HashTable from hashTableImplementation.rs, made durable.
+
+  The table lives in memory. Every change is first appended to a write-ahead log (WAL) and
+  flushed to disk, and only then applied to the table, so a change that was acknowledged
+  survives a crash. Each log record carries a CRC-32 checksum:
+
+      [crc32: u32][op: u8][key length: u32][value length: u32][key][value]
+
+  where op is 1 for a set and 2 for a removal, and the checksum covers everything after itself.
+  All integers are little-endian.
+
+  The log grows with every write, so once it holds many more records than the table has keys,
+  the store writes the whole table to a snapshot file and starts an empty log. The snapshot is
+  written to a temporary file and renamed into place, so a crash leaves either the old snapshot
+  or the new one. If the crash comes after the rename but before the log is emptied, the old log
+  is replayed on top of the new snapshot, which is harmless: replaying sets and removals in order
+  ends in the same state.
+
+  Opening a store loads the snapshot and replays the log. A crash during an append leaves a torn
+  record at the very end of the log; it was never acknowledged, so it is dropped and the log is
+  truncated to the last good record. A bad record anywhere else means the file is damaged, and
+  opening fails rather than silently losing data.
+
+  Build it as `rustc -O kvStore.rs -o kv`, then:
+
+      kv <directory> set <key> <value>
+      kv <directory> get <key>
+      kv <directory> del <key>
+      kv <directory> compact
+*/
+
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Error, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+
+#[allow(dead_code)]
+#[path = "hashTableImplementation.rs"]
+mod hash_table;
+
+use hash_table::HashTable;
+
+/// Computes the CRC-32 (IEEE 802.3) checksum of `bytes`, one bit at a time.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// The identifier at the start of every snapshot file.
+const SNAPSHOT_MAGIC: &[u8; 4] = b"KVS1";
+
+/// The log record type of a set.
+const OP_SET: u8 = 1;
+
+/// The log record type of a removal.
+const OP_REMOVE: u8 = 2;
+
+/// The size of a log record header: checksum, op, key length and value length.
+const RECORD_HEADER: usize = 4 + 1 + 4 + 4;
+
+/// The log is never compacted automatically while it has fewer records than this.
+const MIN_COMPACTION_RECORDS: usize = 1000;
+
+/// The most bytes `KvStore::is_torn_tail` checksums while looking for an intact record after
+/// a damaged one, so that opening a log with a long damaged tail stays O(n).
+const TORN_TAIL_SCAN_LIMIT: usize = 1 << 20;
+
+/// Encodes one log record.
+fn encode_record(op: u8, key: &[u8], value: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(RECORD_HEADER + key.len() + value.len());
+    body.push(op);
+    body.extend_from_slice(&(key.len() as u32).to_le_bytes());
+    body.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    body.extend_from_slice(key);
+    body.extend_from_slice(value);
+
+    let mut record = crc32(&body).to_le_bytes().to_vec();
+    record.extend_from_slice(&body);
+    record
+}
+
+/// Reads the header of the record at the start of `bytes`, without checking anything.
+///
+/// # Returns
+///
+/// The op, key length and total record length, or `None` if the header is incomplete.
+fn decode_header(bytes: &[u8]) -> Option<(u8, usize, usize)> {
+    let header = bytes.get(..RECORD_HEADER)?;
+    let key_length = u32::from_le_bytes(header[5..9].try_into().unwrap()) as usize;
+    let value_length = u32::from_le_bytes(header[9..13].try_into().unwrap()) as usize;
+    Some((
+        header[4],
+        key_length,
+        RECORD_HEADER + key_length + value_length,
+    ))
+}
+
+/// Decodes the record at the start of `bytes`.
+///
+/// # Returns
+///
+/// The op, key, value and total record length, or `None` if the record is incomplete or its
+/// checksum does not match.
+fn decode_record(bytes: &[u8]) -> Option<(u8, &[u8], &[u8], usize)> {
+    let (op, key_length, length) = decode_header(bytes)?;
+    let checksum = u32::from_le_bytes(bytes[0..4].try_into().unwrap());
+    let body = bytes.get(4..length)?;
+    if (op != OP_SET && op != OP_REMOVE) || crc32(body) != checksum {
+        return None;
+    }
+    let key = &bytes[RECORD_HEADER..RECORD_HEADER + key_length];
+    Some((op, key, &bytes[RECORD_HEADER + key_length..length], length))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![0; read_u32(reader)? as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// A durable key-value store kept in one directory.
+struct KvStore {
+    directory: PathBuf,
+    table: HashTable<Vec<u8>, Vec<u8>>,
+    /// The write-ahead log, opened for appending.
+    log: File,
+    /// The number of records in the log.
+    log_records: usize,
+    /// Set when a failed append could not be cut off the log; no further writes are allowed.
+    failed: bool,
+}
+
+impl KvStore {
+    /// Opens the store in `directory`, creating the directory if needed.
+    ///
+    /// The snapshot is loaded and the log replayed on top of it. A torn record at the end of
+    /// the log is dropped and the log truncated to the last complete record.
+    ///
+    /// # Returns
+    ///
+    /// The store, or an `InvalidData` error if the snapshot or the middle of the log is damaged.
+    pub fn open<P: AsRef<Path>>(directory: P) -> io::Result<Self> {
+        let directory = directory.as_ref().to_path_buf();
+        fs::create_dir_all(&directory)?;
+
+        let mut table = HashTable::new(0);
+        match fs::read(directory.join("snapshot")) {
+            Ok(bytes) => Self::load_snapshot(&bytes, &mut table)?,
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+
+        let mut log = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(directory.join("wal"))?;
+        let mut bytes = Vec::new();
+        log.read_to_end(&mut bytes)?;
+
+        let mut offset = 0;
+        let mut log_records = 0;
+        while offset < bytes.len() {
+            let Some((op, key, value, length)) = decode_record(&bytes[offset..]) else {
+                break;
+            };
+            if op == OP_SET {
+                table.set(key.to_vec(), value.to_vec());
+            } else {
+                table.remove(key);
+            }
+            offset += length;
+            log_records += 1;
+        }
+
+        if offset < bytes.len() {
+            if !Self::is_torn_tail(&bytes[offset..]) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("corrupt log record at byte {}", offset),
+                ));
+            }
+            log.set_len(offset as u64)?;
+            log.sync_all()?;
+        }
+
+        Ok(KvStore {
+            directory,
+            table,
+            log,
+            log_records,
+            failed: false,
+        })
+    }
+
+    /// Returns `true` if a record that failed to decode is the interrupted last write, rather
+    /// than damage in the middle of the log: either its header is incomplete, the length it
+    /// declares runs to (or past) the end of the file, or no valid record starts anywhere after
+    /// it (as with a tail the file system zero-filled after a crash).
+    ///
+    /// The search for a valid record gives up and reports damage once it has checksummed
+    /// `TORN_TAIL_SCAN_LIMIT` bytes, since refusing to open loses nothing.
+    fn is_torn_tail(bytes: &[u8]) -> bool {
+        match decode_header(bytes) {
+            None => return true,
+            Some((_, _, length)) if length >= bytes.len() => return true,
+            Some(_) => {}
+        }
+        if bytes.iter().all(|&byte| byte == 0) {
+            return true;
+        }
+
+        let mut budget = TORN_TAIL_SCAN_LIMIT;
+        for start in 1..bytes.len() {
+            let rest = &bytes[start..];
+            let Some((op, _, length)) = decode_header(rest) else {
+                break;
+            };
+            if (op != OP_SET && op != OP_REMOVE) || length > rest.len() {
+                continue;
+            }
+            if length > budget || decode_record(rest).is_some() {
+                return false;
+            }
+            budget -= length;
+        }
+        true
+    }
+
+    /// Reads every entry of a snapshot file into `table`.
+    fn load_snapshot(bytes: &[u8], table: &mut HashTable<Vec<u8>, Vec<u8>>) -> io::Result<()> {
+        let invalid = || Error::new(ErrorKind::InvalidData, "corrupt snapshot");
+        if bytes.len() < 12 || &bytes[..4] != SNAPSHOT_MAGIC {
+            return Err(invalid());
+        }
+        let (contents, checksum) = bytes.split_at(bytes.len() - 4);
+        if crc32(contents) != u32::from_le_bytes(checksum.try_into().unwrap()) {
+            return Err(invalid());
+        }
+
+        let mut reader = &contents[4..];
+        for _ in 0..read_u32(&mut reader)? {
+            let key = read_bytes(&mut reader)?;
+            let value = read_bytes(&mut reader)?;
+            table.set(key, value);
+        }
+        Ok(())
+    }
+
+    /// Returns the number of keys in the store.
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Returns `true` if the store holds no keys.
+    pub fn is_empty(&self) -> bool {
+        self.table.len() == 0
+    }
+
+    /// Returns the number of records in the write-ahead log.
+    pub fn log_records(&self) -> usize {
+        self.log_records
+    }
+
+    /// Retrieves the value stored under `key`.
+    /// This operation runs in O(1) average time and never touches the disk.
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.table.get(key).map(Vec::as_slice)
+    }
+
+    /// Appends a record to the log and waits until it is on disk.
+    ///
+    /// If the write fails, whatever part of the record reached the file is cut off again, so
+    /// the next append does not land behind a torn record. If even that fails, the store
+    /// refuses every later write.
+    fn append(&mut self, op: u8, key: &[u8], value: &[u8]) -> io::Result<()> {
+        if self.failed {
+            return Err(Error::other(
+                "an earlier write failed and could not be undone; reopen the store",
+            ));
+        }
+        let length = self.log.metadata()?.len();
+        let result = self
+            .log
+            .write_all(&encode_record(op, key, value))
+            .and_then(|()| self.log.sync_data());
+        if let Err(err) = result {
+            if self.log.set_len(length).is_err() {
+                self.failed = true;
+            }
+            return Err(err);
+        }
+        self.log_records += 1;
+        Ok(())
+    }
+
+    /// Compacts the log if it has grown well beyond the number of keys.
+    ///
+    /// Called after a write that is already on disk, so a failed compaction is not reported to
+    /// the writer: it leaves the log as it was, and the next write tries again.
+    fn compact_after_write(&mut self) {
+        if self.log_records >= MIN_COMPACTION_RECORDS.max(2 * self.table.len()) {
+            let _ = self.compact();
+        }
+    }
+
+    /// Stores `value` under `key`. The change is on disk when this returns.
+    ///
+    /// # Returns
+    ///
+    /// The value previously stored under the key, if there was one.
+    pub fn set(&mut self, key: &[u8], value: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        self.append(OP_SET, key, value)?;
+        let old = self.table.set(key.to_vec(), value.to_vec());
+        self.compact_after_write();
+        Ok(old)
+    }
+
+    /// Removes `key`. The change is on disk when this returns. Removing a missing key writes
+    /// nothing.
+    ///
+    /// # Returns
+    ///
+    /// The value that was stored under the key, if there was one.
+    pub fn remove(&mut self, key: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        if self.table.get(key).is_none() {
+            return Ok(None);
+        }
+        self.append(OP_REMOVE, key, &[])?;
+        let old = self.table.remove(key);
+        self.compact_after_write();
+        Ok(old)
+    }
+
+    /// Writes the whole table to a new snapshot and empties the log.
+    /// This operation runs in O(n) time.
+    pub fn compact(&mut self) -> io::Result<()> {
+        let mut contents = SNAPSHOT_MAGIC.to_vec();
+        contents.extend_from_slice(&(self.table.len() as u32).to_le_bytes());
+        for (key, value) in self.table.iter() {
+            contents.extend_from_slice(&(key.len() as u32).to_le_bytes());
+            contents.extend_from_slice(key);
+            contents.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            contents.extend_from_slice(value);
+        }
+        let checksum = crc32(&contents);
+        contents.extend_from_slice(&checksum.to_le_bytes());
+
+        let temporary = self.directory.join("snapshot.tmp");
+        let mut writer = BufWriter::new(File::create(&temporary)?);
+        writer.write_all(&contents)?;
+        writer.into_inner()?.sync_all()?;
+        fs::rename(&temporary, self.directory.join("snapshot"))?;
+        // Make the rename itself durable before the log it replaces is emptied.
+        File::open(&self.directory)?.sync_all()?;
+
+        self.log.set_len(0)?;
+        self.log.sync_all()?;
+        self.log_records = 0;
+        Ok(())
+    }
+}
+
+/// Runs one `kv` subcommand against the store in `directory`.
+fn run(directory: &str, command: &[String]) -> io::Result<()> {
+    let mut store = KvStore::open(directory)?;
+    let args: Vec<&str> = command.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["get", key] => match store.get(key.as_bytes()) {
+            Some(value) => println!("{}", String::from_utf8_lossy(value)),
+            None => return Err(Error::new(ErrorKind::NotFound, "key not found")),
+        },
+        ["set", key, value] => {
+            store.set(key.as_bytes(), value.as_bytes())?;
+        }
+        ["del", key] => {
+            if store.remove(key.as_bytes())?.is_none() {
+                return Err(Error::new(ErrorKind::NotFound, "key not found"));
+            }
+        }
+        ["compact"] => {
+            let records = store.log_records();
+            store.compact()?;
+            println!(
+                "compacted {} log records into {} keys",
+                records,
+                store.len()
+            );
+        }
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "usage: kv <directory> get <key> | set <key> <value> | del <key> | compact",
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Shows a store surviving a torn write, in a scratch directory.
+fn demo() -> io::Result<()> {
+    let directory = env::temp_dir().join(format!("kvStore-demo-{}", std::process::id()));
+    {
+        let mut store = KvStore::open(&directory)?;
+        store.set(b"grapes", b"10000")?;
+        store.set(b"apples", b"54")?;
+        store.set(b"oranges", b"2")?;
+        let old = store.set(b"apples", b"55")?;
+        println!(
+            "Old apples: {:?}",
+            old.as_deref().map(String::from_utf8_lossy)
+        );
+        store.remove(b"oranges")?;
+        println!(
+            "{} keys from {} log records, empty: {}",
+            store.len(),
+            store.log_records(),
+            store.is_empty()
+        );
+    }
+
+    // Simulate a crash half-way through appending a record.
+    let record = encode_record(OP_SET, b"pears", b"7");
+    let mut log = OpenOptions::new()
+        .append(true)
+        .open(directory.join("wal"))?;
+    log.write_all(&record[..record.len() / 2])?;
+    drop(log);
+
+    let store = KvStore::open(&directory)?;
+    println!(
+        "After recovery: {} keys, apples = {:?}, pears = {:?}",
+        store.len(),
+        store.get(b"apples").map(String::from_utf8_lossy),
+        store.get(b"pears")
+    );
+    drop(store);
+
+    // Simulate a crash that left the end of the log zero-filled.
+    let mut log = OpenOptions::new()
+        .append(true)
+        .open(directory.join("wal"))?;
+    log.write_all(&[0; 40])?;
+    drop(log);
+
+    let mut store = KvStore::open(&directory)?;
+    println!(
+        "After zero-filled tail: {} keys from {} log records, wal is {} bytes",
+        store.len(),
+        store.log_records(),
+        fs::metadata(directory.join("wal"))?.len()
+    );
+
+    store.compact()?;
+    let store = KvStore::open(&directory)?;
+    println!(
+        "After compaction: {} keys from {} log records, grapes = {:?}",
+        store.len(),
+        store.log_records(),
+        store.get(b"grapes").map(String::from_utf8_lossy)
+    );
+
+    fs::remove_dir_all(&directory)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let result = match args.get(1) {
+        Some(directory) => run(directory, &args[2..]),
+        None => demo(),
+    };
+
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,339 @@
+/*
+  Minimal perfect hashing
+
+  When the set of keys is fixed up front, such as a dictionary loaded at startup that never
+  changes, we can do better than the dynamic HashTable in hashTableImplementation.rs. A
+  minimal perfect hash function maps each of the n keys to its own slot in 0..n, with no
+  collisions and no empty slots, so the values can sit in a plain array of exactly n entries.
+
+  MinimalPerfectHash builds one the way BBHash does. Every key is hashed into a bit array of
+  about gamma * n bits. Keys that land on a bit of their own keep it; keys that share a bit
+  with another key move on to a second, smaller array for the leftovers, with a different hash,
+  and so on until every key has a bit to itself. A key's slot is the number of set bits before
+  its bit, across all levels. With gamma = 2 this takes about 3.7 bits per key, no matter how
+  large the keys themselves are.
+
+  StaticMap stores the keys and values in slot order. A lookup hashes once per level it visits
+  and then compares a single key, with no probing and no chains.
+*/
+
+use std::hash::{BuildHasher, Hash};
+
+#[allow(dead_code)]
+#[path = "hashers.rs"]
+mod hashers;
+
+use hashers::{mix, DefaultHashBuilder};
+
+/// The ratio of bits to keys at each level used by `MinimalPerfectHash::new`.
+const DEFAULT_GAMMA: f64 = 2.0;
+
+/// The number of levels after which the keys are assumed to contain duplicates. With distinct
+/// keys, a share of about 1 - e^(-1/gamma) of each level's keys is left over (39% for gamma 2),
+/// so 64 levels are never needed.
+const MAX_LEVELS: usize = 64;
+
+/// The number of 64-bit words covered by each precomputed rank.
+const RANK_SAMPLE_WORDS: usize = 8;
+
+/// Returns the bit a key's hash picks at `level`, in a level of `size` bits.
+fn position(hash: u64, level: usize, size: usize) -> usize {
+    let level_hash = mix(hash ^ (level as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    // Scales the hash into 0..size without the bias of a remainder.
+    ((level_hash as u128 * size as u128) >> 64) as usize
+}
+
+/// A minimal perfect hash function over a fixed set of keys: each key gets its own slot in
+/// `0..len()`.
+///
+/// Keys outside the set also map to a slot, or to `None`; telling them apart needs the keys
+/// themselves, which `StaticMap` keeps.
+struct MinimalPerfectHash<S = DefaultHashBuilder> {
+    /// The bit arrays of all levels, one after another.
+    bits: Vec<u64>,
+    /// The size of each level, in bits. Always a multiple of 64.
+    level_sizes: Vec<usize>,
+    /// The number of set bits before every `RANK_SAMPLE_WORDS`-th word.
+    ranks: Vec<usize>,
+    length: usize,
+    hash_builder: S,
+}
+
+impl MinimalPerfectHash {
+    /// Builds the function for `keys`, with `DEFAULT_GAMMA`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` contains duplicates.
+    pub fn new<K: Hash>(keys: &[K]) -> Self {
+        Self::with_hasher(keys, DEFAULT_GAMMA, DefaultHashBuilder::default())
+    }
+}
+
+impl<S: BuildHasher> MinimalPerfectHash<S> {
+    /// Builds the function for `keys`, hashing with hashers built by `hash_builder`.
+    /// This operation runs in O(n) expected time.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The key set. Each key is hashed once, to 64 bits, so two keys whose hashes
+    ///   clash count as duplicates; for distinct keys that is vanishingly rare.
+    /// * `gamma` - Bits per key at each level, at least 1. Larger values use more memory but
+    ///   leave fewer keys over for later levels, making construction and lookups faster.
+    /// * `hash_builder` - The hash function.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` contains duplicates or `gamma` is below 1.
+    pub fn with_hasher<K: Hash>(keys: &[K], gamma: f64, hash_builder: S) -> Self {
+        let hashes = keys.iter().map(|key| hash_builder.hash_one(key)).collect();
+        Self::from_hashes(hashes, gamma, hash_builder)
+    }
+
+    fn from_hashes(mut remaining: Vec<u64>, gamma: f64, hash_builder: S) -> Self {
+        assert!(gamma >= 1.0, "gamma must be at least 1");
+        let length = remaining.len();
+        let mut bits = Vec::new();
+        let mut level_sizes = Vec::new();
+
+        while !remaining.is_empty() {
+            assert!(
+                level_sizes.len() < MAX_LEVELS,
+                "the keys of a minimal perfect hash must be distinct"
+            );
+            let level = level_sizes.len();
+            let words = ((remaining.len() as f64 * gamma).ceil() as usize).div_ceil(64);
+            let size = words * 64;
+            let mut taken = vec![0u64; words];
+            let mut collided = vec![0u64; words];
+            for &hash in &remaining {
+                let bit = position(hash, level, size);
+                if taken[bit / 64] & (1 << (bit % 64)) != 0 {
+                    collided[bit / 64] |= 1 << (bit % 64);
+                } else {
+                    taken[bit / 64] |= 1 << (bit % 64);
+                }
+            }
+            for (word, clash) in taken.iter_mut().zip(&collided) {
+                *word &= !clash;
+            }
+            remaining.retain(|&hash| {
+                let bit = position(hash, level, size);
+                collided[bit / 64] & (1 << (bit % 64)) != 0
+            });
+            bits.extend(taken);
+            level_sizes.push(size);
+        }
+
+        let mut ranks = Vec::with_capacity(bits.len() / RANK_SAMPLE_WORDS + 1);
+        let mut count = 0;
+        for (index, word) in bits.iter().enumerate() {
+            if index % RANK_SAMPLE_WORDS == 0 {
+                ranks.push(count);
+            }
+            count += word.count_ones() as usize;
+        }
+
+        MinimalPerfectHash {
+            bits,
+            level_sizes,
+            ranks,
+            length,
+            hash_builder,
+        }
+    }
+
+    /// Returns the number of keys, which is also the number of slots.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the function was built over no keys.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns the number of levels the keys were spread over.
+    pub fn level_count(&self) -> usize {
+        self.level_sizes.len()
+    }
+
+    /// Returns the memory used per key, in bits, including the rank samples.
+    pub fn bits_per_key(&self) -> f64 {
+        let bits = self.bits.len() * 64 + self.ranks.len() * usize::BITS as usize;
+        bits as f64 / self.length.max(1) as f64
+    }
+
+    /// Returns the number of set bits before bit `bit` of the concatenated levels.
+    fn rank(&self, bit: usize) -> usize {
+        let word = bit / 64;
+        let sample = word / RANK_SAMPLE_WORDS;
+        let before: usize = self.bits[sample * RANK_SAMPLE_WORDS..word]
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum();
+        let partial = (self.bits[word] & ((1u64 << (bit % 64)) - 1)).count_ones() as usize;
+        self.ranks[sample] + before + partial
+    }
+
+    /// Returns the slot of `key`, in `0..len()`.
+    /// This operation runs in O(1) expected time: most keys are found on the first level or two.
+    ///
+    /// # Returns
+    ///
+    /// A distinct slot for every key the function was built over. Any other key gets an
+    /// arbitrary slot or `None`.
+    pub fn index<K: Hash + ?Sized>(&self, key: &K) -> Option<usize> {
+        let hash = self.hash_builder.hash_one(key);
+        let mut offset = 0;
+        for (level, &size) in self.level_sizes.iter().enumerate() {
+            let bit = offset + position(hash, level, size);
+            if self.bits[bit / 64] & (1 << (bit % 64)) != 0 {
+                return Some(self.rank(bit));
+            }
+            offset += size;
+        }
+        None
+    }
+}
+
+/// A read-only map over a key set fixed at construction, backed by a `MinimalPerfectHash`.
+///
+/// The entries are stored in slot order, so a lookup is one hash function evaluation and one
+/// key comparison, and the map holds no empty buckets.
+struct StaticMap<K, V, S = DefaultHashBuilder> {
+    hash: MinimalPerfectHash<S>,
+    /// Entry i holds the key whose slot is i.
+    entries: Vec<(K, V)>,
+}
+
+impl<K: Hash + Eq, V> StaticMap<K, V> {
+    /// Builds a map from key-value pairs.
+    /// This operation runs in O(n log n) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two entries have the same key.
+    pub fn new(entries: Vec<(K, V)>) -> Self {
+        Self::with_hasher(entries, DefaultHashBuilder::default())
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> StaticMap<K, V, S> {
+    /// Builds a map from key-value pairs, hashing with hashers built by `hash_builder`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two entries have the same key.
+    pub fn with_hasher(mut entries: Vec<(K, V)>, hash_builder: S) -> Self {
+        let hashes = entries
+            .iter()
+            .map(|(key, _)| hash_builder.hash_one(key))
+            .collect();
+        let hash = MinimalPerfectHash::from_hashes(hashes, DEFAULT_GAMMA, hash_builder);
+        entries.sort_by_cached_key(|(key, _)| hash.index(key));
+        StaticMap { hash, entries }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the value for `key`, if the map holds one.
+    /// This operation runs in O(1) expected time.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let (stored, value) = self.entries.get(self.hash.index(key)?)?;
+        (stored == key).then_some(value)
+    }
+
+    /// Returns `true` if the map holds a value for `key`.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns an iterator over the entries, in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for StaticMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        StaticMap::new(iter.into_iter().collect())
+    }
+}
+
+fn main() {
+    let keys: Vec<String> = (0..100_000).map(|i| format!("key-{}", i)).collect();
+    let hash = MinimalPerfectHash::new(&keys);
+    let mut slots: Vec<usize> = keys.iter().map(|key| hash.index(key).unwrap()).collect();
+    slots.sort_unstable();
+    slots.dedup();
+    println!(
+        "{} keys, {} distinct slots, highest {}, {} levels, {:.2} bits per key",
+        hash.len(),
+        slots.len(),
+        slots.last().unwrap(),
+        hash.level_count(),
+        hash.bits_per_key()
+    );
+    let sparse = MinimalPerfectHash::with_hasher(&keys, 5.0, DefaultHashBuilder::default());
+    println!(
+        "With gamma 5: {} levels, {:.2} bits per key",
+        sparse.level_count(),
+        sparse.bits_per_key()
+    );
+    let empty = MinimalPerfectHash::new::<u32>(&[]);
+    println!(
+        "Empty: {}, index of 7: {:?}",
+        empty.is_empty(),
+        empty.index(&7)
+    );
+
+    let elements: StaticMap<&str, u32> = [
+        ("hydrogen", 1),
+        ("helium", 2),
+        ("lithium", 3),
+        ("carbon", 6),
+        ("nitrogen", 7),
+        ("oxygen", 8),
+        ("iron", 26),
+        ("gold", 79),
+    ]
+    .into_iter()
+    .collect();
+    println!(
+        "{} elements, oxygen: {:?}, gold: {:?}, unobtainium: {:?}",
+        elements.len(),
+        elements.get(&"oxygen"),
+        elements.get(&"gold"),
+        elements.get(&"unobtainium")
+    );
+    println!(
+        "Contains iron: {}, empty: {}",
+        elements.contains_key(&"iron"),
+        elements.is_empty()
+    );
+    let slot_order: Vec<String> = elements
+        .iter()
+        .map(|(name, number)| format!("{}={}", name, number))
+        .collect();
+    println!("In slot order: {}", slot_order.join(", "));
+
+    let squares = StaticMap::with_hasher(
+        (0..1000u64).map(|n| (n, n * n)).collect(),
+        std::collections::hash_map::RandomState::new(),
+    );
+    let all_found = (0..1000u64).all(|n| squares.get(&n) == Some(&(n * n)));
+    println!(
+        "Squares: all found {}, 1000 present {}",
+        all_found,
+        squares.contains_key(&1000)
+    );
+}
@@ -0,0 +1,740 @@
+/*
+  Persistent hash map: a hash array mapped trie (HAMT)
+
+  An immutable map where `insert` and `remove` leave the original untouched and return a new
+  version. Copying the whole table for every change would cost O(n); instead, the map is a
+  trie keyed by the hash, 5 bits per level, so each node has up to 32 children. A change copies
+  only the nodes on the path from the root to the key, about log32(n) of them, and the new
+  version shares every other node with the old one through `Arc`. A million-entry map is four
+  or five levels deep.
+
+  Nodes don't store 32 child pointers. Each keeps a 32-bit bitmap of which children exist and
+  a dense vector of just those, and finds child i at position popcount(bitmap & ((1 << i) - 1)).
+
+  Versions are immutable and reference-counted atomically, so they can be handed to other
+  threads freely: a map is `Send` and `Sync` whenever its keys and values are.
+*/
+
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::sync::Arc;
+use std::thread;
+
+#[allow(dead_code)]
+#[path = "hashers.rs"]
+mod hashers;
+
+use hashers::DefaultHashBuilder;
+
+/// The number of hash bits consumed at each level of the trie.
+const BITS_PER_LEVEL: u32 = 5;
+
+/// Selects a level's bits from the hash.
+const LEVEL_MASK: u64 = (1 << BITS_PER_LEVEL) - 1;
+
+/// One occupied child position of a node.
+enum Slot<K, V> {
+    /// A single entry, with its full hash so it can be pushed down a level without rehashing.
+    Leaf(u64, K, V),
+    /// A subtrie for the keys sharing this position.
+    Node(Arc<Node<K, V>>),
+}
+
+/// A trie node. Below the last level, where every hash bit has been used, a node instead holds
+/// the entries whose full 64-bit hashes collide, unordered, and `bitmap` is unused.
+struct Node<K, V> {
+    bitmap: u32,
+    slots: Vec<Slot<K, V>>,
+}
+
+impl<K: Clone, V: Clone> Clone for Slot<K, V> {
+    fn clone(&self) -> Self {
+        match self {
+            Slot::Leaf(hash, key, value) => Slot::Leaf(*hash, key.clone(), value.clone()),
+            Slot::Node(node) => Slot::Node(Arc::clone(node)),
+        }
+    }
+}
+
+/// What removing a key did to a subtrie.
+enum Removal<K, V> {
+    /// The key was not there; the subtrie is unchanged.
+    NotFound,
+    /// The subtrie became empty.
+    Empty,
+    /// Only one entry is left, to be lifted into the parent in place of the subtrie.
+    Single(Slot<K, V>),
+    /// The subtrie was copied without the key.
+    Changed(Node<K, V>),
+}
+
+impl<K: Eq + Clone, V: Clone> Node<K, V> {
+    fn empty() -> Self {
+        Node {
+            bitmap: 0,
+            slots: Vec::new(),
+        }
+    }
+
+    /// Returns the bit for `hash` at the level starting at bit `shift`, and the position its
+    /// slot has (or would have) in `slots`.
+    fn locate(&self, hash: u64, shift: u32) -> (u32, usize) {
+        let bit = 1 << ((hash >> shift) & LEVEL_MASK);
+        (bit, (self.bitmap & (bit - 1)).count_ones() as usize)
+    }
+
+    fn get<Q>(&self, hash: u64, shift: u32, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        if shift >= u64::BITS {
+            return self.slots.iter().find_map(|slot| match slot {
+                Slot::Leaf(_, k, v) if k.borrow() == key => Some(v),
+                _ => None,
+            });
+        }
+        let (bit, position) = self.locate(hash, shift);
+        if self.bitmap & bit == 0 {
+            return None;
+        }
+        match &self.slots[position] {
+            Slot::Leaf(h, k, v) => (*h == hash && k.borrow() == key).then_some(v),
+            Slot::Node(node) => node.get(hash, shift + BITS_PER_LEVEL, key),
+        }
+    }
+
+    /// Builds the smallest subtrie, starting at bit `shift`, that holds two leaves.
+    fn pair(shift: u32, first: Slot<K, V>, second: Slot<K, V>) -> Self {
+        let hash_of = |slot: &Slot<K, V>| match slot {
+            Slot::Leaf(hash, _, _) => *hash,
+            Slot::Node(_) => unreachable!("only leaves are paired"),
+        };
+        let (first_hash, second_hash) = (hash_of(&first), hash_of(&second));
+        if shift >= u64::BITS {
+            return Node {
+                bitmap: 0,
+                slots: vec![first, second],
+            };
+        }
+        let first_index = (first_hash >> shift) & LEVEL_MASK;
+        let second_index = (second_hash >> shift) & LEVEL_MASK;
+        if first_index == second_index {
+            Node {
+                bitmap: 1 << first_index,
+                slots: vec![Slot::Node(Arc::new(Self::pair(
+                    shift + BITS_PER_LEVEL,
+                    first,
+                    second,
+                )))],
+            }
+        } else {
+            let slots = if first_index < second_index {
+                vec![first, second]
+            } else {
+                vec![second, first]
+            };
+            Node {
+                bitmap: (1 << first_index) | (1 << second_index),
+                slots,
+            }
+        }
+    }
+
+    /// Returns a copy of this node with `key` set to `value`, and whether the key is new.
+    fn insert(&self, hash: u64, shift: u32, key: K, value: V) -> (Self, bool) {
+        let mut slots = self.slots.clone();
+        if shift >= u64::BITS {
+            let existing = slots
+                .iter_mut()
+                .find(|slot| matches!(slot, Slot::Leaf(_, k, _) if *k == key));
+            let added = existing.is_none();
+            match existing {
+                Some(slot) => *slot = Slot::Leaf(hash, key, value),
+                None => slots.push(Slot::Leaf(hash, key, value)),
+            }
+            return (Node { bitmap: 0, slots }, added);
+        }
+
+        let (bit, position) = self.locate(hash, shift);
+        if self.bitmap & bit == 0 {
+            slots.insert(position, Slot::Leaf(hash, key, value));
+            return (
+                Node {
+                    bitmap: self.bitmap | bit,
+                    slots,
+                },
+                true,
+            );
+        }
+        let added = match &self.slots[position] {
+            Slot::Leaf(h, k, _) if *h == hash && *k == key => {
+                slots[position] = Slot::Leaf(hash, key, value);
+                false
+            }
+            Slot::Leaf(..) => {
+                let resident = slots[position].clone();
+                let pair = Self::pair(
+                    shift + BITS_PER_LEVEL,
+                    resident,
+                    Slot::Leaf(hash, key, value),
+                );
+                slots[position] = Slot::Node(Arc::new(pair));
+                true
+            }
+            Slot::Node(node) => {
+                let (child, added) = node.insert(hash, shift + BITS_PER_LEVEL, key, value);
+                slots[position] = Slot::Node(Arc::new(child));
+                added
+            }
+        };
+        (
+            Node {
+                bitmap: self.bitmap,
+                slots,
+            },
+            added,
+        )
+    }
+
+    /// Returns what is left of this node without `key`. Subtries left with a single leaf are
+    /// collapsed into it, so every version has the same shape as a map built from scratch.
+    fn remove<Q>(&self, hash: u64, shift: u32, key: &Q) -> Removal<K, V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        let position = if shift >= u64::BITS {
+            match self
+                .slots
+                .iter()
+                .position(|slot| matches!(slot, Slot::Leaf(_, k, _) if k.borrow() == key))
+            {
+                Some(position) => position,
+                None => return Removal::NotFound,
+            }
+        } else {
+            let (bit, position) = self.locate(hash, shift);
+            if self.bitmap & bit == 0 {
+                return Removal::NotFound;
+            }
+            match &self.slots[position] {
+                Slot::Leaf(h, k, _) if *h == hash && k.borrow() == key => position,
+                Slot::Leaf(..) => return Removal::NotFound,
+                Slot::Node(node) => {
+                    let replacement = match node.remove(hash, shift + BITS_PER_LEVEL, key) {
+                        Removal::NotFound => return Removal::NotFound,
+                        Removal::Empty => None,
+                        Removal::Single(slot) => Some(slot),
+                        Removal::Changed(child) => Some(Slot::Node(Arc::new(child))),
+                    };
+                    if let Some(slot) = replacement {
+                        if self.slots.len() == 1 && matches!(slot, Slot::Leaf(..)) {
+                            return Removal::Single(slot);
+                        }
+                        let mut slots = self.slots.clone();
+                        slots[position] = slot;
+                        return Removal::Changed(Node {
+                            bitmap: self.bitmap,
+                            slots,
+                        });
+                    }
+                    position
+                }
+            }
+        };
+
+        // Drop the slot at `position` from this node.
+        let mut slots = self.slots.clone();
+        slots.remove(position);
+        let bitmap = if shift >= u64::BITS {
+            0
+        } else {
+            self.bitmap & !(1 << ((hash >> shift) & LEVEL_MASK))
+        };
+        match slots.len() {
+            0 => Removal::Empty,
+            1 if matches!(slots[0], Slot::Leaf(..)) => Removal::Single(slots.pop().unwrap()),
+            _ => Removal::Changed(Node { bitmap, slots }),
+        }
+    }
+
+    /// Counts this node and all nodes below it.
+    fn count_nodes(&self) -> usize {
+        1 + self
+            .slots
+            .iter()
+            .map(|slot| match slot {
+                Slot::Leaf(..) => 0,
+                Slot::Node(node) => node.count_nodes(),
+            })
+            .sum::<usize>()
+    }
+}
+
+/// An immutable hash map whose `insert` and `remove` return new versions that share most of
+/// their structure with the old one.
+///
+/// Cloning a map is O(1): it only bumps the reference count of the root. `insert` and
+/// `remove` run in O(log32 n) time, copying the nodes along one path.
+struct PersistentHashMap<K, V, S = DefaultHashBuilder> {
+    root: Arc<Node<K, V>>,
+    length: usize,
+    hash_builder: S,
+}
+
+impl<K, V, S: Clone> Clone for PersistentHashMap<K, V, S> {
+    fn clone(&self) -> Self {
+        PersistentHashMap {
+            root: Arc::clone(&self.root),
+            length: self.length,
+            hash_builder: self.hash_builder.clone(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> PersistentHashMap<K, V> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::with_hasher(DefaultHashBuilder::default())
+    }
+}
+
+impl<K, V, S> PersistentHashMap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    S: BuildHasher + Clone,
+{
+    /// Creates an empty map that hashes its keys with hashers built by `hash_builder`.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        PersistentHashMap {
+            root: Arc::new(Node::empty()),
+            length: 0,
+            hash_builder,
+        }
+    }
+
+    /// Returns the number of key-value pairs in the map.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the map holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns the value for `key`, if the map holds one.
+    /// This operation runs in O(log32 n) time.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.root.get(self.hash_builder.hash_one(key), 0, key)
+    }
+
+    /// Returns `true` if the map holds a value for the given key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Returns a new version of the map with `key` set to `value`. `self` is unchanged.
+    /// This operation runs in O(log32 n) time.
+    pub fn insert(&self, key: K, value: V) -> Self {
+        let hash = self.hash_builder.hash_one(&key);
+        let (root, added) = self.root.insert(hash, 0, key, value);
+        PersistentHashMap {
+            root: Arc::new(root),
+            length: self.length + added as usize,
+            hash_builder: self.hash_builder.clone(),
+        }
+    }
+
+    /// Returns a new version of the map without `key`. `self` is unchanged. If the key is
+    /// absent, the new version shares the whole trie with `self`.
+    /// This operation runs in O(log32 n) time.
+    pub fn remove<Q>(&self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let root = match self.root.remove(self.hash_builder.hash_one(key), 0, key) {
+            Removal::NotFound => return self.clone(),
+            Removal::Empty => Node::empty(),
+            Removal::Single(slot) => {
+                // The root keeps its leaves, since it has no parent to lift them into.
+                let hash = match &slot {
+                    Slot::Leaf(hash, _, _) => *hash,
+                    Slot::Node(_) => unreachable!("only leaves are lifted"),
+                };
+                Node {
+                    bitmap: 1 << (hash & LEVEL_MASK),
+                    slots: vec![slot],
+                }
+            }
+            Removal::Changed(node) => node,
+        };
+        PersistentHashMap {
+            root: Arc::new(root),
+            length: self.length - 1,
+            hash_builder: self.hash_builder.clone(),
+        }
+    }
+
+    /// Returns an iterator over all key-value pairs, in hash order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            stack: vec![self.root.slots.iter()],
+            remaining: self.length,
+        }
+    }
+
+    /// Returns an iterator over all keys stored in the map.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over all values stored in the map.
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Returns `true` if both maps are the very same version, sharing one root.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.root, &other.root)
+    }
+
+    /// Returns the number of trie nodes in this version, counting shared ones.
+    pub fn node_count(&self) -> usize {
+        self.root.count_nodes()
+    }
+}
+
+/// An iterator over the entries of a `PersistentHashMap`, walking the trie depth first with
+/// an explicit stack of at most 14 levels.
+struct Iter<'a, K, V> {
+    stack: Vec<std::slice::Iter<'a, Slot<K, V>>>,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(slots) = self.stack.last_mut() {
+            match slots.next() {
+                Some(Slot::Leaf(_, key, value)) => {
+                    self.remaining -= 1;
+                    return Some((key, value));
+                }
+                Some(Slot::Node(node)) => self.stack.push(node.slots.iter()),
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V, S> PartialEq for PersistentHashMap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Clone + PartialEq,
+    S: BuildHasher + Clone,
+{
+    /// Two maps are equal if they hold the same entries. Versions that share their root are
+    /// equal at once; otherwise this takes O(n log32 n) time.
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other)
+            || (self.len() == other.len()
+                && self
+                    .iter()
+                    .all(|(key, value)| other.get(key) == Some(value)))
+    }
+}
+
+impl<K, V, S> Eq for PersistentHashMap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Clone + Eq,
+    S: BuildHasher + Clone,
+{
+}
+
+impl<K, V, S> fmt::Debug for PersistentHashMap<K, V, S>
+where
+    K: Hash + Eq + Clone + fmt::Debug,
+    V: Clone + fmt::Debug,
+    S: BuildHasher + Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, S> FromIterator<(K, V)> for PersistentHashMap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    S: BuildHasher + Clone + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::with_hasher(S::default()), |map, (key, value)| {
+                map.insert(key, value)
+            })
+    }
+}
+
+/// An immutable set on a `PersistentHashMap` with no values, with the same sharing between
+/// versions.
+struct PersistentHashSet<T, S = DefaultHashBuilder> {
+    map: PersistentHashMap<T, (), S>,
+}
+
+impl<T, S: Clone> Clone for PersistentHashSet<T, S> {
+    fn clone(&self) -> Self {
+        PersistentHashSet {
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone> PersistentHashSet<T> {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        PersistentHashSet {
+            map: PersistentHashMap::new(),
+        }
+    }
+}
+
+impl<T, S> PersistentHashSet<T, S>
+where
+    T: Hash + Eq + Clone,
+    S: BuildHasher + Clone,
+{
+    /// Returns the number of values in the set.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the set holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns `true` if the set holds `value`.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Returns a new version of the set that also holds `value`.
+    pub fn insert(&self, value: T) -> Self {
+        PersistentHashSet {
+            map: self.map.insert(value, ()),
+        }
+    }
+
+    /// Returns a new version of the set without `value`.
+    pub fn remove<Q>(&self, value: &Q) -> Self
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        PersistentHashSet {
+            map: self.map.remove(value),
+        }
+    }
+
+    /// Returns an iterator over the values, in hash order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.map.keys()
+    }
+}
+
+impl<T, S> PartialEq for PersistentHashSet<T, S>
+where
+    T: Hash + Eq + Clone,
+    S: BuildHasher + Clone,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<T, S> fmt::Debug for PersistentHashSet<T, S>
+where
+    T: Hash + Eq + Clone + fmt::Debug,
+    S: BuildHasher + Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T, S> FromIterator<T> for PersistentHashSet<T, S>
+where
+    T: Hash + Eq + Clone,
+    S: BuildHasher + Clone + Default,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        PersistentHashSet {
+            map: iter.into_iter().map(|value| (value, ())).collect(),
+        }
+    }
+}
+
+/// Counts the nodes of `new` that are shared with `old` rather than copied.
+fn shared_nodes<K, V>(old: &Node<K, V>, new: &Node<K, V>) -> usize {
+    fn collect<K, V>(node: &Node<K, V>, into: &mut Vec<*const Node<K, V>>) {
+        for slot in &node.slots {
+            if let Slot::Node(child) = slot {
+                into.push(Arc::as_ptr(child));
+                collect(child, into);
+            }
+        }
+    }
+    let (mut before, mut after) = (Vec::new(), Vec::new());
+    collect(old, &mut before);
+    collect(new, &mut after);
+    let before: HashSet<_> = before.into_iter().collect();
+    after.iter().filter(|node| before.contains(node)).count()
+}
+
+/// Hashes every key to the same value, to exercise the collision nodes below the last level.
+#[derive(Clone, Default)]
+struct ConstantHasher;
+
+impl std::hash::Hasher for ConstantHasher {
+    fn write(&mut self, _bytes: &[u8]) {}
+
+    fn finish(&self) -> u64 {
+        42
+    }
+}
+
+impl BuildHasher for ConstantHasher {
+    type Hasher = ConstantHasher;
+
+    fn build_hasher(&self) -> ConstantHasher {
+        ConstantHasher
+    }
+}
+
+fn main() {
+    let config: PersistentHashMap<String, String> = [
+        ("host", "localhost"),
+        ("port", "8080"),
+        ("log_level", "info"),
+    ]
+    .into_iter()
+    .map(|(key, value)| (key.to_string(), value.to_string()))
+    .collect();
+    let updated = config
+        .insert("port".to_string(), "9090".to_string())
+        .remove("log_level");
+    println!("Original: {:?}", config);
+    println!("Updated:  {:?}", updated);
+    println!(
+        "Original port {:?}, updated port {:?}, updated has log_level: {}",
+        config.get("port"),
+        updated.get("port"),
+        updated.contains_key("log_level")
+    );
+
+    // Readers on other threads each keep the version they were handed.
+    thread::scope(|scope| {
+        for version in [config.clone(), updated.clone()] {
+            scope.spawn(move || {
+                let mut values: Vec<&String> = version.values().collect();
+                values.sort();
+                println!("A reader sees {} entries: {:?}", version.len(), values);
+            });
+        }
+    });
+
+    let big: PersistentHashMap<u32, u32> = (0..100_000).map(|n| (n, n * 2)).collect();
+    let changed = big.insert(12_345, 0);
+    println!(
+        "100000 entries in {} nodes; after one insert {} of the new version's {} nodes are shared",
+        big.node_count(),
+        shared_nodes(&big.root, &changed.root),
+        changed.node_count()
+    );
+    println!(
+        "Old value {:?}, new value {:?}",
+        big.get(&12_345),
+        changed.get(&12_345)
+    );
+    let unchanged = big.remove(&1_000_000);
+    println!(
+        "Removing a missing key shares the root: {}, equal: {}",
+        unchanged.ptr_eq(&big),
+        unchanged == big
+    );
+    let restored = changed.insert(12_345, 24_690);
+    println!(
+        "Changed back: equal {}, same root {}, {} entries",
+        restored == big,
+        restored.ptr_eq(&big),
+        restored.iter().count()
+    );
+    let emptied = (0..100_000).fold(big.clone(), |map, n| map.remove(&n));
+    println!(
+        "Removed everything: empty {}, {} node, still {} entries in the original",
+        emptied.is_empty(),
+        emptied.node_count(),
+        big.keys().count()
+    );
+
+    let colliding = PersistentHashMap::with_hasher(ConstantHasher)
+        .insert("a", 1)
+        .insert("b", 2)
+        .insert("c", 3)
+        .insert("b", 20);
+    let fewer = colliding.remove(&"a").remove(&"c");
+    println!(
+        "All keys colliding: {:?} in {} nodes, after removals {:?} in {} node",
+        colliding,
+        colliding.node_count(),
+        fewer,
+        fewer.node_count()
+    );
+
+    let primes: PersistentHashSet<u32> = [2, 3, 5, 7, 11].into_iter().collect();
+    let more = primes.insert(13).remove(&2);
+    let mut sorted: Vec<&u32> = more.iter().collect();
+    sorted.sort();
+    println!(
+        "Primes {:?} ({} values), then {:?}; contains 2: {} then {}",
+        primes,
+        primes.len(),
+        sorted,
+        primes.contains(&2),
+        more.contains(&2)
+    );
+    let empty = PersistentHashSet::new().insert(1).remove(&1);
+    println!(
+        "Set emptied: {}, equal to a fresh set: {}",
+        empty.is_empty(),
+        empty == PersistentHashSet::new()
+    );
+}
@@ -0,0 +1,636 @@
+/*
+  Probabilistic membership filters: compact summaries of a set that answer "is x in the set?"
+  with either "definitely not" or "probably yes". They never give false negatives, and the
+  false-positive rate is traded against memory. A HashTable that has to store every key is
+  exact but costs the keys themselves; a filter needs around 10 bits per key for a 1% error.
+
+  - BloomFilter: k hash functions each set one bit of a bit array.
+  - CountingBloomFilter: the same with a small counter per position instead of a bit, so
+    items can be removed again.
+  - CuckooFilter: stores a 16-bit fingerprint per item in one of two candidate buckets, like
+    cuckooHashTable.rs stores whole entries. Supports removal, and at low error rates uses
+    less space than a Bloom filter.
+
+  Every filter can be written to a byte buffer and read back, so it can be built in one process
+  and queried in another. Items are hashed with SipHash-2-4 under a 128-bit key that is stored
+  with the filter, so the same item hashes the same way everywhere, while the bit positions
+  stay unpredictable to anyone who does not know the key.
+*/
+
+use std::hash::{Hash, Hasher};
+use std::io::{self, Error, ErrorKind, Read};
+
+#[allow(dead_code)]
+#[path = "hashers.rs"]
+mod hashers;
+
+use hashers::{SeededSipHash, SipHasher24};
+
+/// Hashes `item` under `key`, with `salt` selecting one of several independent hashes.
+fn salted_hash<T: Hash + ?Sized>(key: &SeededSipHash, salt: u64, item: &T) -> u64 {
+    let mut hasher = SipHasher24::with_keys(key.k0() ^ salt, key.k1());
+    item.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns `count` bit positions in `0..size` for `item`, by double hashing: the i-th
+/// position is h1 + i * h2, which behaves like `count` independent hashes.
+fn positions<T: Hash + ?Sized>(
+    key: &SeededSipHash,
+    item: &T,
+    count: u32,
+    size: u64,
+) -> impl Iterator<Item = usize> {
+    let h1 = salted_hash(key, 0, item);
+    let h2 = salted_hash(key, 0x9e37_79b9_7f4a_7c15, item) | 1;
+    (0..count as u64).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % size) as usize)
+}
+
+/// Appends the header shared by all serialized filters.
+fn write_header(bytes: &mut Vec<u8>, magic: &[u8; 4], key: &SeededSipHash) {
+    bytes.extend_from_slice(magic);
+    bytes.extend_from_slice(&key.k0().to_le_bytes());
+    bytes.extend_from_slice(&key.k1().to_le_bytes());
+}
+
+/// Reads the header written by `write_header`, checking the magic bytes.
+fn read_header(reader: &mut &[u8], magic: &[u8; 4]) -> io::Result<SeededSipHash> {
+    let mut found = [0; 4];
+    reader.read_exact(&mut found)?;
+    if &found != magic {
+        return Err(Error::new(ErrorKind::InvalidData, "wrong filter type"));
+    }
+    let k0 = read_u64(reader)?;
+    Ok(SeededSipHash::new(k0, read_u64(reader)?))
+}
+
+fn read_u64(reader: &mut &[u8]) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_u32(reader: &mut &[u8]) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Checks that a declared size is plausible before allocating for it.
+fn check_size(size: u64, element_bytes: u64, remaining: &[u8]) -> io::Result<usize> {
+    if size == 0 || size.saturating_mul(element_bytes) > remaining.len() as u64 {
+        return Err(Error::new(ErrorKind::InvalidData, "truncated filter"));
+    }
+    Ok(size as usize)
+}
+
+/// Returns the bit count and hash count of a Bloom filter that holds `capacity` items at a
+/// false-positive rate of `false_positive_rate`: m = -n ln p / (ln 2)^2 and k = (m / n) ln 2.
+fn bloom_dimensions(capacity: usize, false_positive_rate: f64) -> (u64, u32) {
+    assert!(
+        capacity > 0,
+        "a filter must have room for at least one item"
+    );
+    assert!(
+        false_positive_rate > 0.0 && false_positive_rate < 1.0,
+        "the false-positive rate must be between 0 and 1"
+    );
+    let ln2 = std::f64::consts::LN_2;
+    let bits = (-(capacity as f64) * false_positive_rate.ln() / (ln2 * ln2)).ceil();
+    let hashes = (bits / capacity as f64 * ln2).round().max(1.0);
+    (bits as u64, hashes as u32)
+}
+
+/// A Bloom filter: inserting an item sets `hash_count` bits, and an item whose bits are not
+/// all set was certainly never inserted.
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    bit_count: u64,
+    hash_count: u32,
+    /// The number of `insert` calls, including repeats.
+    items: u64,
+    key: SeededSipHash,
+}
+
+impl BloomFilter {
+    /// Creates a filter that keeps its false-positive rate at or below `false_positive_rate`
+    /// until `capacity` items have been inserted.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The number of items the filter is sized for. Must be positive.
+    /// * `false_positive_rate` - The target error rate, between 0 and 1 exclusive.
+    pub fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        let (bit_count, hash_count) = bloom_dimensions(capacity, false_positive_rate);
+        BloomFilter {
+            bits: vec![0; bit_count.div_ceil(64) as usize],
+            bit_count,
+            hash_count,
+            items: 0,
+            key: SeededSipHash::random(),
+        }
+    }
+
+    /// Returns the number of bits in the filter.
+    pub fn bit_count(&self) -> u64 {
+        self.bit_count
+    }
+
+    /// Returns the number of bits set per item.
+    pub fn hash_count(&self) -> u32 {
+        self.hash_count
+    }
+
+    /// Adds an item to the filter.
+    /// This operation runs in O(k) time for k hash functions.
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+        for bit in positions(&self.key, item, self.hash_count, self.bit_count) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+        self.items += 1;
+    }
+
+    /// Returns `false` if `item` was certainly never inserted, and `true` if it probably was.
+    pub fn contains<T: Hash + ?Sized>(&self, item: &T) -> bool {
+        positions(&self.key, item, self.hash_count, self.bit_count)
+            .all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Estimates the current false-positive rate from the fraction of bits set: a random item
+    /// that was never inserted is reported present if all k of its bits happen to be set.
+    pub fn estimated_false_positive_rate(&self) -> f64 {
+        let set: u32 = self.bits.iter().map(|word| word.count_ones()).sum();
+        (set as f64 / self.bit_count as f64).powi(self.hash_count as i32)
+    }
+
+    /// Serializes the filter as `"BLM1" | key | bit count | hash count | items | bits`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(48 + self.bits.len() * 8);
+        write_header(&mut bytes, b"BLM1", &self.key);
+        bytes.extend_from_slice(&self.bit_count.to_le_bytes());
+        bytes.extend_from_slice(&self.hash_count.to_le_bytes());
+        bytes.extend_from_slice(&self.items.to_le_bytes());
+        for word in &self.bits {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Reads a filter written by `to_bytes`.
+    pub fn from_bytes(mut bytes: &[u8]) -> io::Result<Self> {
+        let reader = &mut bytes;
+        let key = read_header(reader, b"BLM1")?;
+        let bit_count = read_u64(reader)?;
+        let hash_count = read_u32(reader)?;
+        let items = read_u64(reader)?;
+        let words = check_size(bit_count.div_ceil(64), 8, reader)?;
+        let bits = (0..words)
+            .map(|_| read_u64(reader))
+            .collect::<io::Result<_>>()?;
+        Ok(BloomFilter {
+            bits,
+            bit_count,
+            hash_count,
+            items,
+            key,
+        })
+    }
+}
+
+/// A Bloom filter with an 8-bit counter in place of every bit, so items can be removed.
+///
+/// A counter that reaches 255 stays there for good: after that many collisions it can no
+/// longer tell how many items share it, and decrementing it could cause false negatives.
+struct CountingBloomFilter {
+    counters: Vec<u8>,
+    hash_count: u32,
+    /// The number of items currently inserted.
+    items: u64,
+    key: SeededSipHash,
+}
+
+impl CountingBloomFilter {
+    /// Creates a filter sized like `BloomFilter::new`, with one counter per bit.
+    pub fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        let (counter_count, hash_count) = bloom_dimensions(capacity, false_positive_rate);
+        CountingBloomFilter {
+            counters: vec![0; counter_count as usize],
+            hash_count,
+            items: 0,
+            key: SeededSipHash::random(),
+        }
+    }
+
+    /// Returns the number of items currently in the filter.
+    pub fn len(&self) -> u64 {
+        self.items
+    }
+
+    fn positions<T: Hash + ?Sized>(&self, item: &T) -> impl Iterator<Item = usize> {
+        positions(&self.key, item, self.hash_count, self.counters.len() as u64)
+    }
+
+    /// Adds an item to the filter.
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+        for position in self.positions(item) {
+            self.counters[position] = self.counters[position].saturating_add(1);
+        }
+        self.items += 1;
+    }
+
+    /// Returns `false` if `item` is certainly not in the filter, and `true` if it probably is.
+    pub fn contains<T: Hash + ?Sized>(&self, item: &T) -> bool {
+        self.positions(item)
+            .all(|position| self.counters[position] > 0)
+    }
+
+    /// Removes one copy of an item. Only remove items that were inserted: removing a false
+    /// positive decrements counters that belong to other items.
+    ///
+    /// # Returns
+    ///
+    /// `false`, changing nothing, if the item is certainly not in the filter.
+    pub fn remove<T: Hash + ?Sized>(&mut self, item: &T) -> bool {
+        if !self.contains(item) {
+            return false;
+        }
+        for position in self.positions(item) {
+            if self.counters[position] < u8::MAX {
+                self.counters[position] -= 1;
+            }
+        }
+        // A saturated counter can keep a never-inserted item looking present after the
+        // filter has emptied, so removing it must not take the count below zero.
+        self.items = self.items.saturating_sub(1);
+        true
+    }
+
+    /// Estimates the current false-positive rate from the fraction of non-zero counters.
+    pub fn estimated_false_positive_rate(&self) -> f64 {
+        let used = self.counters.iter().filter(|&&count| count > 0).count();
+        (used as f64 / self.counters.len() as f64).powi(self.hash_count as i32)
+    }
+
+    /// Serializes the filter as `"CBF1" | key | counter count | hash count | items | counters`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(48 + self.counters.len());
+        write_header(&mut bytes, b"CBF1", &self.key);
+        bytes.extend_from_slice(&(self.counters.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.hash_count.to_le_bytes());
+        bytes.extend_from_slice(&self.items.to_le_bytes());
+        bytes.extend_from_slice(&self.counters);
+        bytes
+    }
+
+    /// Reads a filter written by `to_bytes`.
+    pub fn from_bytes(mut bytes: &[u8]) -> io::Result<Self> {
+        let reader = &mut bytes;
+        let key = read_header(reader, b"CBF1")?;
+        let counter_count = read_u64(reader)?;
+        let hash_count = read_u32(reader)?;
+        let items = read_u64(reader)?;
+        let length = check_size(counter_count, 1, reader)?;
+        let mut counters = vec![0; length];
+        reader.read_exact(&mut counters)?;
+        Ok(CountingBloomFilter {
+            counters,
+            hash_count,
+            items,
+            key,
+        })
+    }
+}
+
+/// The number of fingerprints each cuckoo filter bucket holds.
+const BUCKET_SIZE: usize = 4;
+
+/// The number of evictions an insert tries before declaring the filter full.
+const MAX_KICKS: usize = 500;
+
+/// A cuckoo filter: each item leaves a 16-bit fingerprint in one of two buckets.
+///
+/// The second bucket is the first XOR a hash of the fingerprint, so either bucket can be
+/// found from the other and the fingerprint alone. That is what lets the filter move a
+/// fingerprint to its other bucket to make room, without knowing the original item.
+struct CuckooFilter {
+    /// `BUCKET_SIZE` fingerprints per bucket; 0 marks an empty slot.
+    fingerprints: Vec<u16>,
+    /// A power of two, so that XOR keeps bucket indexes in range.
+    bucket_count: usize,
+    items: u64,
+    /// A fingerprint that was evicted by the last failed insert and had nowhere to go, kept so
+    /// no inserted item is ever forgotten.
+    victim: Option<(usize, u16)>,
+    key: SeededSipHash,
+    /// The state of the xorshift generator that picks which fingerprint to evict.
+    rng: u64,
+}
+
+impl CuckooFilter {
+    /// Creates a filter with room for about `capacity` items; inserts start failing at around
+    /// 95% occupancy.
+    pub fn new(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "a filter must have room for at least one item"
+        );
+        let bucket_count = (capacity as f64 / (BUCKET_SIZE as f64 * 0.95))
+            .ceil()
+            .max(1.0) as usize;
+        let bucket_count = bucket_count.next_power_of_two();
+        let key = SeededSipHash::random();
+        CuckooFilter {
+            fingerprints: vec![0; bucket_count * BUCKET_SIZE],
+            bucket_count,
+            items: 0,
+            victim: None,
+            rng: key.k0() | 1,
+            key,
+        }
+    }
+
+    /// Returns the number of items currently in the filter.
+    pub fn len(&self) -> u64 {
+        self.items
+    }
+
+    /// Returns the fraction of fingerprint slots in use.
+    pub fn load_factor(&self) -> f64 {
+        self.items as f64 / self.fingerprints.len() as f64
+    }
+
+    /// Returns the fingerprint and first bucket of `item`.
+    fn locate<T: Hash + ?Sized>(&self, item: &T) -> (u16, usize) {
+        let hash = salted_hash(&self.key, 0, item);
+        // Zero marks an empty slot, so fingerprints run from 1 to 65535.
+        let fingerprint = ((hash >> 48) % 0xffff) as u16 + 1;
+        (fingerprint, (hash as usize) & (self.bucket_count - 1))
+    }
+
+    /// Returns the other bucket a fingerprint in `bucket` may live in.
+    fn alternate(&self, bucket: usize, fingerprint: u16) -> usize {
+        (bucket ^ salted_hash(&self.key, 1, &fingerprint) as usize) & (self.bucket_count - 1)
+    }
+
+    fn bucket(&mut self, bucket: usize) -> &mut [u16] {
+        &mut self.fingerprints[bucket * BUCKET_SIZE..(bucket + 1) * BUCKET_SIZE]
+    }
+
+    /// Puts `fingerprint` in a free slot of `bucket`, if there is one.
+    fn try_put(&mut self, bucket: usize, fingerprint: u16) -> bool {
+        match self.bucket(bucket).iter_mut().find(|slot| **slot == 0) {
+            Some(slot) => {
+                *slot = fingerprint;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Adds an item to the filter.
+    /// This operation runs in O(1) amortized time while the filter is not close to full.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the filter is full. The insert that fills the filter still records its item;
+    /// later ones record nothing until something is removed.
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) -> bool {
+        if self.victim.is_some() {
+            return false;
+        }
+        let (mut fingerprint, first) = self.locate(item);
+        let second = self.alternate(first, fingerprint);
+        self.items += 1;
+        if self.try_put(first, fingerprint) || self.try_put(second, fingerprint) {
+            return true;
+        }
+
+        let mut bucket = if self.rng & 1 == 0 { first } else { second };
+        for _ in 0..MAX_KICKS {
+            self.rng ^= self.rng << 13;
+            self.rng ^= self.rng >> 7;
+            self.rng ^= self.rng << 17;
+            let slot = self.rng as usize % BUCKET_SIZE;
+            std::mem::swap(&mut self.bucket(bucket)[slot], &mut fingerprint);
+            bucket = self.alternate(bucket, fingerprint);
+            if self.try_put(bucket, fingerprint) {
+                return true;
+            }
+        }
+        self.victim = Some((bucket, fingerprint));
+        false
+    }
+
+    /// Returns `false` if `item` is certainly not in the filter, and `true` if it probably is.
+    pub fn contains<T: Hash + ?Sized>(&self, item: &T) -> bool {
+        let (fingerprint, first) = self.locate(item);
+        let second = self.alternate(first, fingerprint);
+        let in_bucket = |bucket: usize| {
+            self.fingerprints[bucket * BUCKET_SIZE..(bucket + 1) * BUCKET_SIZE]
+                .contains(&fingerprint)
+        };
+        in_bucket(first)
+            || in_bucket(second)
+            || self.victim.is_some_and(|(bucket, victim)| {
+                victim == fingerprint && (bucket == first || bucket == second)
+            })
+    }
+
+    /// Removes one copy of an item. As with `CountingBloomFilter::remove`, only remove items
+    /// that were inserted.
+    ///
+    /// # Returns
+    ///
+    /// `false`, changing nothing, if the item is certainly not in the filter.
+    pub fn remove<T: Hash + ?Sized>(&mut self, item: &T) -> bool {
+        let (fingerprint, first) = self.locate(item);
+        let second = self.alternate(first, fingerprint);
+
+        if let Some((bucket, victim)) = self.victim {
+            if victim == fingerprint && (bucket == first || bucket == second) {
+                self.victim = None;
+                self.items -= 1;
+                return true;
+            }
+        }
+        for bucket in [first, second] {
+            if let Some(slot) = self
+                .bucket(bucket)
+                .iter_mut()
+                .find(|slot| **slot == fingerprint)
+            {
+                *slot = 0;
+                self.items -= 1;
+                // A slot is free again, so the victim can move back into the table.
+                if let Some((bucket, victim)) = self.victim.take() {
+                    if !self.try_put(bucket, victim)
+                        && !self.try_put(self.alternate(bucket, victim), victim)
+                    {
+                        self.victim = Some((bucket, victim));
+                    }
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Estimates the current false-positive rate: a lookup compares against the fingerprints
+    /// in two buckets, each of which matches by chance with probability 1 / 65535.
+    pub fn estimated_false_positive_rate(&self) -> f64 {
+        let compared = (2 * BUCKET_SIZE) as f64 * self.load_factor();
+        1.0 - (1.0 - 1.0 / 65535.0f64).powf(compared)
+    }
+
+    /// Serializes the filter as
+    /// `"CKF1" | key | bucket count | items | victim bucket + 1 or 0 | victim | fingerprints`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(48 + self.fingerprints.len() * 2);
+        write_header(&mut bytes, b"CKF1", &self.key);
+        bytes.extend_from_slice(&(self.bucket_count as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.items.to_le_bytes());
+        let (bucket, victim) = self
+            .victim
+            .map_or((0, 0), |(bucket, victim)| (bucket as u64 + 1, victim));
+        bytes.extend_from_slice(&bucket.to_le_bytes());
+        bytes.extend_from_slice(&victim.to_le_bytes());
+        for fingerprint in &self.fingerprints {
+            bytes.extend_from_slice(&fingerprint.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Reads a filter written by `to_bytes`.
+    pub fn from_bytes(mut bytes: &[u8]) -> io::Result<Self> {
+        let reader = &mut bytes;
+        let key = read_header(reader, b"CKF1")?;
+        let bucket_count = read_u64(reader)?;
+        let items = read_u64(reader)?;
+        let victim_bucket = read_u64(reader)?;
+        let mut victim = [0; 2];
+        reader.read_exact(&mut victim)?;
+        if !bucket_count.is_power_of_two() || victim_bucket > bucket_count {
+            return Err(Error::new(ErrorKind::InvalidData, "corrupt cuckoo filter"));
+        }
+
+        let slots = check_size(bucket_count.saturating_mul(BUCKET_SIZE as u64), 2, reader)?;
+        let mut fingerprints = Vec::with_capacity(slots);
+        for _ in 0..slots {
+            let mut fingerprint = [0; 2];
+            reader.read_exact(&mut fingerprint)?;
+            fingerprints.push(u16::from_le_bytes(fingerprint));
+        }
+        // `remove` counts items down as it clears fingerprints, so the count has to match them.
+        let victim =
+            (victim_bucket > 0).then(|| (victim_bucket as usize - 1, u16::from_le_bytes(victim)));
+        let stored =
+            fingerprints.iter().filter(|&&slot| slot != 0).count() as u64 + victim.is_some() as u64;
+        if items != stored || victim.is_some_and(|(_, victim)| victim == 0) {
+            return Err(Error::new(ErrorKind::InvalidData, "corrupt cuckoo filter"));
+        }
+        Ok(CuckooFilter {
+            fingerprints,
+            bucket_count: bucket_count as usize,
+            items,
+            victim,
+            rng: key.k0() | 1,
+            key,
+        })
+    }
+}
+
+/// Counts how many of `count` never-inserted items `contains` claims to have seen.
+fn measured_false_positive_rate(contains: impl Fn(&str) -> bool, count: usize) -> f64 {
+    let hits = (0..count)
+        .filter(|i| contains(&format!("stranger-{}", i)))
+        .count();
+    hits as f64 / count as f64
+}
+
+fn main() {
+    let users: Vec<String> = (0..10_000).map(|i| format!("user-{}", i)).collect();
+
+    let mut bloom = BloomFilter::new(users.len(), 0.01);
+    for user in &users {
+        bloom.insert(user.as_str());
+    }
+    println!(
+        "Bloom filter: {} bits ({:.1} per item), {} hashes",
+        bloom.bit_count(),
+        bloom.bit_count() as f64 / users.len() as f64,
+        bloom.hash_count()
+    );
+    println!(
+        "  estimated false positives {:.4}, measured {:.4}",
+        bloom.estimated_false_positive_rate(),
+        measured_false_positive_rate(|item| bloom.contains(item), 100_000)
+    );
+    let shipped = BloomFilter::from_bytes(&bloom.to_bytes()).unwrap();
+    println!(
+        "  after a round trip through {} bytes, contains user-42: {}",
+        bloom.to_bytes().len(),
+        shipped.contains("user-42")
+    );
+
+    let mut counting = CountingBloomFilter::new(users.len(), 0.01);
+    for user in &users {
+        counting.insert(user.as_str());
+    }
+    for user in &users[..5_000] {
+        counting.remove(user.as_str());
+    }
+    let shipped = CountingBloomFilter::from_bytes(&counting.to_bytes()).unwrap();
+    println!(
+        "Counting Bloom filter after removing half: {} items, contains user-1: {}, user-9999: {}",
+        shipped.len(),
+        shipped.contains("user-1"),
+        shipped.contains("user-9999")
+    );
+    println!(
+        "  estimated false positives {:.4}, measured {:.4}",
+        counting.estimated_false_positive_rate(),
+        measured_false_positive_rate(|item| counting.contains(item), 100_000)
+    );
+
+    let mut cuckoo = CuckooFilter::new(users.len());
+    let stored = users
+        .iter()
+        .filter(|user| cuckoo.insert(user.as_str()))
+        .count();
+    println!(
+        "Cuckoo filter: stored {} of {} at load {:.2}",
+        stored,
+        users.len(),
+        cuckoo.load_factor()
+    );
+    println!(
+        "  estimated false positives {:.6}, measured {:.6}",
+        cuckoo.estimated_false_positive_rate(),
+        measured_false_positive_rate(|item| cuckoo.contains(item), 1_000_000)
+    );
+    cuckoo.remove("user-7");
+    let shipped = CuckooFilter::from_bytes(&cuckoo.to_bytes()).unwrap();
+    println!(
+        "  after removing user-7: contains user-7: {}, user-8: {}, {} items",
+        shipped.contains("user-7"),
+        shipped.contains("user-8"),
+        shipped.len()
+    );
+
+    // Fill a small filter until it refuses more items.
+    let mut small = CuckooFilter::new(100);
+    let accepted = (0..1_000u32).take_while(|i| small.insert(i)).count() as u32;
+    println!(
+        "A cuckoo filter for 100 items filled up after {} inserts (load {:.2})",
+        accepted + 1,
+        small.load_factor()
+    );
+    println!(
+        "  every inserted item still found: {}",
+        (0..=accepted).all(|i| small.contains(&i))
+    );
+    println!(
+        "  loading a Bloom filter from these bytes fails: {}",
+        BloomFilter::from_bytes(&small.to_bytes()).is_err()
+    );
+}
@@ -0,0 +1,193 @@
+/*
+  A concurrent hash map made of independently locked shards.
+
+  Guarding one map with one Mutex (as 11_dynamicProgramming/memoization.rs used to) makes every
+  thread wait on the same lock, even when they touch unrelated keys. Splitting the map into N
+  shards, each a HashTable behind its own Mutex, means two threads only contend when their keys
+  hash to the same shard.
+
+  For memoization the map also offers `get_or_insert_with`, which runs the computation at most
+  once per key even when several threads ask for the same key at the same time. The shard lock
+  is only held long enough to find or create a per-key `OnceLock`; the computation itself runs
+  outside the shard lock, so a slow computation never blocks other keys, and a memoized
+  function may recursively look up other keys in the same map.
+*/
+
+use std::hash::{BuildHasher, Hash};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+
+#[allow(dead_code)]
+#[path = "hashTableImplementation.rs"]
+pub mod hash_table;
+
+use hash_table::hashers::DefaultHashBuilder;
+use hash_table::HashTable;
+
+/// A hash map that can be shared between threads, split into independently locked shards.
+///
+/// Values are returned by clone, since a reference into a shard cannot outlive its lock.
+/// The map is `Send + Sync` whenever `K: Send` and `V: Send + Sync`.
+pub struct ShardedHashTable<K, V> {
+    /// Each value sits in a `OnceLock` so `get_or_insert_with` can claim a key before its value exists.
+    shards: Vec<Mutex<HashTable<K, Arc<OnceLock<V>>>>>,
+}
+
+impl<K, V> ShardedHashTable<K, V>
+where
+    K: Eq + Hash,
+    V: Clone,
+{
+    /// Creates a new sharded hash table.
+    ///
+    /// # Arguments
+    ///
+    /// * `shard_count` - The number of independently locked shards. A few times the number of
+    ///   threads is a good starting point. Must be at least 1.
+    pub fn new(shard_count: usize) -> Self {
+        assert!(shard_count > 0, "a sharded table needs at least one shard");
+        ShardedHashTable {
+            shards: (0..shard_count)
+                .map(|_| Mutex::new(HashTable::new(0)))
+                .collect(),
+        }
+    }
+
+    /// Returns the number of shards.
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Locks and returns the shard responsible for `key`.
+    ///
+    /// The shard is chosen from the high half of the hash, because each shard's HashTable picks
+    /// its bucket from the low bits; reusing those would leave most buckets of every shard empty.
+    fn shard(&self, key: &K) -> std::sync::MutexGuard<'_, HashTable<K, Arc<OnceLock<V>>>> {
+        let hash = DefaultHashBuilder::default().hash_one(key);
+        let index = ((hash >> 32) as usize) % self.shards.len();
+        // A panic while a shard was locked cannot leave the HashTable half-updated, so a
+        // poisoned lock is still safe to use.
+        self.shards[index]
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns the number of key-value pairs stored, summed over all shards.
+    /// Other threads may change the count while the shards are being visited.
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                let shard = shard
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                shard.len()
+            })
+            .sum()
+    }
+
+    /// Returns `true` if no shard holds a key-value pair.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Inserts a key-value pair, replacing the value if the key already exists.
+    ///
+    /// # Returns
+    ///
+    /// The value previously associated with the key, or `None` if the key is new.
+    pub fn set(&self, key: K, value: V) -> Option<V> {
+        let old = self.shard(&key).set(key, Arc::new(OnceLock::from(value)));
+        old.and_then(|cell| cell.get().cloned())
+    }
+
+    /// Retrieves a copy of the value stored under `key`.
+    /// A key whose value is still being computed by `get_or_insert_with` is reported as missing.
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.shard(key)
+            .get(key)
+            .and_then(|cell| cell.get().cloned())
+    }
+
+    /// Returns `true` if the table holds a value for `key`.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes `key` and returns its value.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let removed = self.shard(key).remove(key);
+        removed.and_then(|cell| cell.get().cloned())
+    }
+
+    /// Returns the value stored under `key`, computing and storing it with `f` if it is missing.
+    ///
+    /// `f` runs at most once per key: if several threads ask for the same missing key, one of
+    /// them computes it and the others wait for the result. The shard stays unlocked while `f`
+    /// runs, so `f` may look up other keys of this table.
+    pub fn get_or_insert_with<F: FnOnce() -> V>(&self, key: K, f: F) -> V {
+        let cell = {
+            let mut shard = self.shard(&key);
+            match shard.get(&key) {
+                Some(cell) => Arc::clone(cell),
+                None => {
+                    let cell = Arc::new(OnceLock::new());
+                    shard.set(key, Arc::clone(&cell));
+                    cell
+                }
+            }
+        };
+        cell.get_or_init(f).clone()
+    }
+}
+
+/// A deliberately slow function, standing in for an expensive computation.
+fn add_to_80(n: i32, calls: &AtomicUsize) -> i32 {
+    calls.fetch_add(1, Ordering::Relaxed);
+    thread::sleep(std::time::Duration::from_millis(10));
+    n + 80
+}
+
+/// A memoized Fibonacci that looks up smaller inputs in the same table while computing.
+fn fibonacci(n: u64, cache: &ShardedHashTable<u64, u64>) -> u64 {
+    if n < 2 {
+        return n;
+    }
+    cache.get_or_insert_with(n, || fibonacci(n - 1, cache) + fibonacci(n - 2, cache))
+}
+
+fn main() {
+    let cache = ShardedHashTable::new(16);
+    let calls = AtomicUsize::new(0);
+
+    // Eight threads ask for the same 20 inputs; each input is only ever computed once.
+    thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                for n in 0..20 {
+                    let answer = cache.get_or_insert_with(n, || add_to_80(n, &calls));
+                    assert_eq!(answer, n + 80);
+                }
+            });
+        }
+    });
+    println!(
+        "{} entries in {} shards after {} computations",
+        cache.len(),
+        cache.shard_count(),
+        calls.load(Ordering::Relaxed)
+    );
+
+    println!("Cached value for 5: {:?}", cache.get(&5));
+    println!("Old value for 5: {:?}", cache.set(5, 0));
+    println!("Removed 6: {:?}", cache.remove(&6));
+    println!(
+        "Contains 6: {}, empty: {}",
+        cache.contains_key(&6),
+        cache.is_empty()
+    );
+
+    let fib_cache = ShardedHashTable::new(4);
+    println!("fibonacci(90) = {}", fibonacci(90, &fib_cache));
+}
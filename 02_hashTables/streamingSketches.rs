@@ -0,0 +1,476 @@
+/*
+  Sketches for unbounded streams: fixed-size summaries that answer questions about a stream
+  far too large to store, in exchange for a small, bounded error.
+
+  - HyperLogLog estimates how many distinct items a stream holds, in a few kilobytes whether it
+    holds a thousand or a billion. Two sketches of the same kind can be merged, giving the
+    count of the union, so streams can be counted in pieces.
+  - CountMinSketch estimates how often each item occurred, never too low and too high by at
+    most a fraction of the stream's length. HeavyHitters uses it to track the most frequent items.
+  - Reservoir keeps a uniform random sample of a stream of unknown length.
+
+  The sketches are generic over `S: BuildHasher`, like the hash tables in this directory, and
+  default to the keyed SipHash-2-4 from hashers.rs under a fixed key, so that sketches built
+  in different runs hash alike and can be merged.
+*/
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+
+#[allow(dead_code)]
+#[path = "hashTableImplementation.rs"]
+mod hash_table;
+
+use hash_table::hashers::SeededSipHash;
+use hash_table::HashTable;
+
+/// The registers of a HyperLogLog.
+#[derive(Clone)]
+enum Registers {
+    /// Only the non-zero registers, as `index << 8 | value`, sorted by index.
+    Sparse(Vec<u32>),
+    /// One byte per register.
+    Dense(Vec<u8>),
+}
+
+/// A HyperLogLog cardinality estimator with 2^p registers.
+///
+/// Each item's hash picks a register with its first p bits, and the register keeps the
+/// largest number of leading zeros (plus one) seen in the remaining bits. Among n distinct
+/// items, a run of about log2(n / 2^p) zeros is expected, so the registers' harmonic mean
+/// gives the count, with a relative standard error of 1.04 / sqrt(2^p).
+///
+/// A fresh sketch starts sparse, storing only the registers that are in use, and switches to
+/// one byte per register once that would be smaller.
+#[derive(Clone)]
+struct HyperLogLog<S = SeededSipHash> {
+    precision: u32,
+    registers: Registers,
+    hash_builder: S,
+}
+
+impl HyperLogLog {
+    /// Creates an empty sketch with 2^`precision` registers, hashing with the default key.
+    ///
+    /// # Arguments
+    ///
+    /// * `precision` - Between 4 and 16. Each step up halves the variance and doubles the
+    ///   memory; 14 gives a standard error of 0.8% in 16 KiB.
+    pub fn new(precision: u32) -> Self {
+        Self::with_hasher(precision, SeededSipHash::default())
+    }
+}
+
+impl<S: BuildHasher> HyperLogLog<S> {
+    /// Creates an empty sketch that hashes items with hashers built by `hash_builder`.
+    pub fn with_hasher(precision: u32, hash_builder: S) -> Self {
+        assert!(
+            (4..=16).contains(&precision),
+            "the precision must be between 4 and 16"
+        );
+        HyperLogLog {
+            precision,
+            registers: Registers::Sparse(Vec::new()),
+            hash_builder,
+        }
+    }
+
+    fn register_count(&self) -> usize {
+        1 << self.precision
+    }
+
+    /// Returns `true` while the sketch still uses the sparse representation.
+    pub fn is_sparse(&self) -> bool {
+        matches!(self.registers, Registers::Sparse(_))
+    }
+
+    /// Returns the number of bytes the registers occupy.
+    pub fn memory_bytes(&self) -> usize {
+        match &self.registers {
+            Registers::Sparse(entries) => entries.len() * 4,
+            Registers::Dense(registers) => registers.len(),
+        }
+    }
+
+    /// Returns the expected relative error of `estimate`, one standard deviation.
+    pub fn standard_error(&self) -> f64 {
+        1.04 / (self.register_count() as f64).sqrt()
+    }
+
+    /// Raises register `index` to at least `value`.
+    fn update(&mut self, index: usize, value: u8) {
+        match &mut self.registers {
+            Registers::Dense(registers) => {
+                registers[index] = registers[index].max(value);
+            }
+            Registers::Sparse(entries) => {
+                let position = entries.partition_point(|entry| (entry >> 8) < index as u32);
+                match entries.get_mut(position) {
+                    Some(entry) if (*entry >> 8) == index as u32 => {
+                        *entry = (*entry).max((index as u32) << 8 | value as u32);
+                    }
+                    _ => entries.insert(position, (index as u32) << 8 | value as u32),
+                }
+                // A sparse entry takes four bytes, a dense register one.
+                if entries.len() * 4 >= self.register_count() {
+                    self.densify();
+                }
+            }
+        }
+    }
+
+    /// Switches to one byte per register.
+    fn densify(&mut self) {
+        if let Registers::Sparse(entries) = &self.registers {
+            let mut registers = vec![0; self.register_count()];
+            for entry in entries {
+                registers[(entry >> 8) as usize] = *entry as u8;
+            }
+            self.registers = Registers::Dense(registers);
+        }
+    }
+
+    /// Adds an item to the sketch. Adding an item again changes nothing.
+    /// This operation runs in O(1) time once the sketch is dense.
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+        let hash = self.hash_builder.hash_one(item);
+        let index = (hash >> (64 - self.precision)) as usize;
+        // The remaining bits, with a sentinel one so the run of zeros is at most 64 - p long.
+        let rest = (hash << self.precision) | (1 << (self.precision - 1));
+        self.update(index, rest.leading_zeros() as u8 + 1);
+    }
+
+    /// Estimates the number of distinct items added so far.
+    /// This operation runs in O(2^p) time.
+    pub fn estimate(&self) -> f64 {
+        let m = self.register_count() as f64;
+        let (sum, zeros) = match &self.registers {
+            Registers::Dense(registers) => registers.iter().fold((0.0, 0), |(sum, zeros), &r| {
+                (sum + 2f64.powi(-(r as i32)), zeros + (r == 0) as usize)
+            }),
+            Registers::Sparse(entries) => {
+                let used: f64 = entries
+                    .iter()
+                    .map(|entry| 2f64.powi(-((entry & 0xff) as i32)))
+                    .sum();
+                let zeros = self.register_count() - entries.len();
+                (used + zeros as f64, zeros)
+            }
+        };
+
+        let alpha = match self.register_count() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let raw = alpha * m * m / sum;
+        // With many empty registers, counting them ("linear counting") is more accurate.
+        if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        }
+    }
+
+    /// Merges `other` into this sketch, which then estimates the size of the union of both
+    /// streams. Both sketches must have the same precision and hash function.
+    pub fn merge(&mut self, other: &Self)
+    where
+        S: PartialEq,
+    {
+        assert!(
+            self.precision == other.precision && self.hash_builder == other.hash_builder,
+            "only sketches with the same precision and hash function can be merged"
+        );
+        match &other.registers {
+            Registers::Sparse(entries) => {
+                for entry in entries {
+                    self.update((entry >> 8) as usize, *entry as u8);
+                }
+            }
+            Registers::Dense(theirs) => {
+                self.densify();
+                if let Registers::Dense(ours) = &mut self.registers {
+                    for (mine, &their) in ours.iter_mut().zip(theirs) {
+                        *mine = (*mine).max(their);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A Count–Min sketch: `depth` rows of `width` counters, each row with its own hash.
+///
+/// An item's count is kept in one counter per row. Other items share those counters, so each
+/// one can only overestimate; the smallest of them is the estimate. With width e / ε and
+/// depth ln(1 / δ), the estimate exceeds the true count by more than ε times the stream length
+/// with probability at most δ.
+///
+/// Updates are conservative: a counter is only raised as far as the item's new estimate,
+/// rather than by the full amount, which cuts the overestimate considerably. The price is
+/// that counts can only ever be added, never subtracted.
+struct CountMinSketch<S = SeededSipHash> {
+    counters: Vec<u64>,
+    width: usize,
+    depth: usize,
+    /// The sum of all counts added.
+    total: u64,
+    hash_builder: S,
+}
+
+impl CountMinSketch {
+    /// Creates a sketch whose estimates exceed the truth by at most `epsilon` times the stream
+    /// length, except with probability `delta`.
+    ///
+    /// # Arguments
+    ///
+    /// * `epsilon` - The error bound as a fraction of the stream length, between 0 and 1.
+    /// * `delta` - The probability of exceeding that bound, between 0 and 1.
+    pub fn new(epsilon: f64, delta: f64) -> Self {
+        assert!(
+            epsilon > 0.0 && epsilon < 1.0,
+            "epsilon must be between 0 and 1"
+        );
+        assert!(delta > 0.0 && delta < 1.0, "delta must be between 0 and 1");
+        let width = (std::f64::consts::E / epsilon).ceil() as usize;
+        let depth = (1.0 / delta).ln().ceil().max(1.0) as usize;
+        Self::with_dimensions(width, depth, SeededSipHash::default())
+    }
+}
+
+impl<S: BuildHasher> CountMinSketch<S> {
+    /// Creates a sketch with explicit dimensions and hash function.
+    pub fn with_dimensions(width: usize, depth: usize, hash_builder: S) -> Self {
+        assert!(
+            width > 0 && depth > 0,
+            "a sketch needs at least one counter"
+        );
+        CountMinSketch {
+            counters: vec![0; width * depth],
+            width,
+            depth,
+            total: 0,
+            hash_builder,
+        }
+    }
+
+    /// Returns the sum of all counts added.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns the index of `item`'s counter in every row, from one hash by double hashing.
+    fn cells<T: Hash + ?Sized>(&self, item: &T) -> impl Iterator<Item = usize> {
+        let hash = self.hash_builder.hash_one(item);
+        let (h1, h2) = (hash & 0xffff_ffff, (hash >> 32) | 1);
+        let width = self.width;
+        (0..self.depth).map(move |row| {
+            row * width + (h1.wrapping_add((row as u64).wrapping_mul(h2)) % width as u64) as usize
+        })
+    }
+
+    /// Adds `count` occurrences of `item`, and returns its new estimated count.
+    /// This operation runs in O(depth) time.
+    pub fn add<T: Hash + ?Sized>(&mut self, item: &T, count: u64) -> u64 {
+        let cells: Vec<usize> = self.cells(item).collect();
+        let estimate = cells.iter().map(|&cell| self.counters[cell]).min().unwrap() + count;
+        for cell in cells {
+            self.counters[cell] = self.counters[cell].max(estimate);
+        }
+        self.total += count;
+        estimate
+    }
+
+    /// Estimates how often `item` has occurred. Never lower than the true count.
+    pub fn estimate<T: Hash + ?Sized>(&self, item: &T) -> u64 {
+        self.cells(item)
+            .map(|cell| self.counters[cell])
+            .min()
+            .unwrap()
+    }
+}
+
+/// Tracks the items that make up more than a given fraction of a stream, using a
+/// `CountMinSketch` for the counts and keeping only the current candidates in memory.
+///
+/// Every item whose estimate crosses the threshold becomes a candidate; candidates that have
+/// fallen below it are dropped whenever the list grows past twice the most there could be.
+/// Since estimates never undercount, no true heavy hitter is ever missed.
+struct HeavyHitters<T, S = SeededSipHash> {
+    sketch: CountMinSketch<S>,
+    /// The fraction of the stream an item must exceed to be reported.
+    threshold: f64,
+    candidates: HashTable<T, u64, SeededSipHash>,
+}
+
+impl<T: Hash + Eq + Clone> HeavyHitters<T> {
+    /// Creates a tracker for items that exceed `threshold` of the stream, with a sketch
+    /// accurate to a tenth of that.
+    pub fn new(threshold: f64) -> Self {
+        assert!(
+            threshold > 0.0 && threshold < 1.0,
+            "the threshold must be between 0 and 1"
+        );
+        HeavyHitters {
+            sketch: CountMinSketch::new(threshold / 10.0, 0.001),
+            threshold,
+            candidates: HashTable::with_hasher(0, SeededSipHash::default()),
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone, S: BuildHasher> HeavyHitters<T, S> {
+    /// Records one occurrence of `item`.
+    pub fn add(&mut self, item: &T) {
+        let estimate = self.sketch.add(item, 1);
+        let cutoff = self.threshold * self.sketch.total() as f64;
+        if estimate as f64 > cutoff {
+            self.candidates.set(item.clone(), estimate);
+            if self.candidates.len() as f64 > 2.0 / self.threshold {
+                self.candidates.retain(|_, count| *count as f64 > cutoff);
+            }
+        }
+    }
+
+    /// Returns the items above the threshold with their estimated counts, most frequent first.
+    pub fn heavy_hitters(&self) -> Vec<(T, u64)> {
+        let cutoff = self.threshold * self.sketch.total() as f64;
+        let mut hitters: Vec<(T, u64)> = self
+            .candidates
+            .keys()
+            .map(|item| (item.clone(), self.sketch.estimate(item)))
+            .filter(|(_, count)| *count as f64 > cutoff)
+            .collect();
+        hitters.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        hitters
+    }
+}
+
+/// A uniform random sample of up to `capacity` items from a stream of unknown length
+/// (Algorithm R). After n items, each of them is in the sample with probability capacity / n.
+struct Reservoir<T> {
+    sample: Vec<T>,
+    capacity: usize,
+    seen: u64,
+    /// The state of the xorshift generator.
+    rng: u64,
+}
+
+impl<T> Reservoir<T> {
+    /// Creates an empty reservoir, seeded from the operating system's randomness.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_seed(capacity, RandomState::new().hash_one(0u8))
+    }
+
+    /// Creates an empty reservoir with a fixed seed, for reproducible samples.
+    pub fn with_seed(capacity: usize, seed: u64) -> Self {
+        assert!(capacity > 0, "a reservoir must hold at least one item");
+        Reservoir {
+            sample: Vec::with_capacity(capacity),
+            capacity,
+            seen: 0,
+            rng: seed | 1,
+        }
+    }
+
+    /// Returns a random number in `0..bound`.
+    fn random_below(&mut self, bound: u64) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        // Scaling the 64-bit value down avoids the bias of taking a remainder.
+        ((self.rng as u128 * bound as u128) >> 64) as u64
+    }
+
+    /// Offers the next item of the stream. The n-th item replaces a random member of the
+    /// sample with probability capacity / n. This operation runs in O(1) time.
+    pub fn add(&mut self, item: T) {
+        self.seen += 1;
+        if self.sample.len() < self.capacity {
+            self.sample.push(item);
+        } else {
+            let slot = self.random_below(self.seen);
+            if slot < self.capacity as u64 {
+                self.sample[slot as usize] = item;
+            }
+        }
+    }
+
+    /// Returns the number of items offered so far.
+    pub fn seen(&self) -> u64 {
+        self.seen
+    }
+
+    /// Returns the current sample.
+    pub fn sample(&self) -> &[T] {
+        &self.sample
+    }
+}
+
+fn main() {
+    // Two servers each count their visitors; merging gives the distinct visitors overall.
+    let mut monday = HyperLogLog::new(14);
+    let mut tuesday = HyperLogLog::new(14);
+    for visitor in 0..600_000u64 {
+        if visitor == 100 {
+            println!(
+                "After 100 visitors: sparse {}, {} bytes",
+                monday.is_sparse(),
+                monday.memory_bytes()
+            );
+        }
+        monday.insert(&visitor);
+    }
+    for visitor in 400_000..1_000_000u64 {
+        tuesday.insert(&visitor);
+    }
+    println!(
+        "Monday: ~{:.0}, Tuesday: ~{:.0} (true 600000 each)",
+        monday.estimate(),
+        tuesday.estimate()
+    );
+    monday.merge(&tuesday);
+    println!(
+        "Both days: ~{:.0} (true 1000000, standard error {:.1}%), {} bytes",
+        monday.estimate(),
+        100.0 * monday.standard_error(),
+        monday.memory_bytes()
+    );
+    let mut keyed = HyperLogLog::with_hasher(10, SeededSipHash::new(7, 7));
+    keyed.insert("one item");
+    keyed.merge(&keyed.clone());
+    println!("A keyed sketch of one item: ~{:.2}", keyed.estimate());
+
+    // A skewed stream of words, where word i appears about 10000 / i times.
+    let mut words = CountMinSketch::new(0.001, 0.01);
+    let mut hitters = HeavyHitters::new(0.05);
+    let mut reservoir = Reservoir::with_seed(5, 42);
+    for rank in 1..=2000u64 {
+        for _ in 0..10_000 / rank {
+            let word = format!("word-{}", rank);
+            words.add(&word, 1);
+            hitters.add(&word);
+            reservoir.add(word);
+        }
+    }
+    println!(
+        "Stream of {} words: word-1 ~{} (true 10000), word-50 ~{} (true 200)",
+        words.total(),
+        words.estimate("word-1"),
+        words.estimate("word-50")
+    );
+    println!(
+        "Words above 5% of the stream: {:?}",
+        hitters.heavy_hitters()
+    );
+    println!(
+        "A sample of 5 out of {}: {:?}",
+        reservoir.seen(),
+        reservoir.sample()
+    );
+    let mut unseeded = Reservoir::new(3);
+    (0..10).for_each(|i| unseeded.add(i));
+    println!("Three of 0..10: {} items", unseeded.sample().len());
+}
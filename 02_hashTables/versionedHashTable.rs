@@ -0,0 +1,345 @@
+/*
+  A multi-version hash table.
+
+  Instead of overwriting a value, every write appends a new version of the key, stamped with a
+  table-wide version number that goes up by one per write. A removal appends a tombstone. Reads
+  can then ask for the value a key had at any version: the answer is the newest entry of that
+  key at or below the requested version.
+
+  A snapshot remembers the version it was taken at and shares the table's storage, so taking
+  one copies nothing, and it keeps answering reads as of that version however many writes
+  come after it.
+
+  Histories only ever grow, so they must be trimmed: `collect_garbage(watermark)` drops every
+  version that no read at or above the watermark can see. Versions still visible to a live
+  snapshot are kept, whatever watermark is given.
+*/
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::hash::Hash;
+use std::rc::Rc;
+
+#[allow(dead_code)]
+#[path = "hashTableImplementation.rs"]
+mod hash_table;
+
+use hash_table::HashTable;
+
+/// One entry in the history of a key.
+struct Version<V> {
+    version: u64,
+    /// The value written at this version, or `None` if the key was removed.
+    value: Option<V>,
+}
+
+/// Returns the value visible at `version` in a history sorted by ascending version.
+/// This operation runs in O(log h) time, where h is the length of the history.
+fn value_at<V>(history: &[Version<V>], version: u64) -> Option<&V> {
+    let newer = history.partition_point(|entry| entry.version <= version);
+    history[..newer].last()?.value.as_ref()
+}
+
+/// The storage shared between a table and its snapshots.
+struct Storage<K, V> {
+    histories: HashTable<K, Vec<Version<V>>>,
+    /// How many live snapshots were taken at each version.
+    snapshots: BTreeMap<u64, usize>,
+    /// Versions below this have been garbage-collected and can no longer be read.
+    oldest_readable: u64,
+}
+
+impl<K, V> Storage<K, V>
+where
+    K: Eq + Hash,
+{
+    fn get_at(&self, key: &K, version: u64) -> Option<&V> {
+        assert!(
+            version >= self.oldest_readable,
+            "version {} has been garbage-collected; the oldest readable version is {}",
+            version,
+            self.oldest_readable
+        );
+        value_at(self.histories.get(key)?, version)
+    }
+}
+
+/// A hash map that keeps the history of every key.
+struct VersionedHashTable<K, V> {
+    storage: Rc<RefCell<Storage<K, V>>>,
+    /// The version of the latest write, or 0 if nothing has been written.
+    version: u64,
+    /// The number of keys that currently hold a value.
+    length: usize,
+}
+
+/// A read-only view of a `VersionedHashTable` as it was when the snapshot was taken.
+/// Returned by `VersionedHashTable::snapshot`.
+struct Snapshot<K, V> {
+    storage: Rc<RefCell<Storage<K, V>>>,
+    version: u64,
+}
+
+impl<K, V> VersionedHashTable<K, V>
+where
+    K: Eq + Hash,
+    V: Clone,
+{
+    /// Creates a new, empty versioned hash table.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The initial number of buckets in the underlying hash table.
+    pub fn new(size: usize) -> Self {
+        VersionedHashTable {
+            storage: Rc::new(RefCell::new(Storage {
+                histories: HashTable::new(size),
+                snapshots: BTreeMap::new(),
+                oldest_readable: 0,
+            })),
+            version: 0,
+            length: 0,
+        }
+    }
+
+    /// Returns the version of the latest write.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns the number of keys that currently hold a value.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if no key currently holds a value.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Appends a new version of `key` to its history.
+    fn write(&mut self, key: K, value: Option<V>) -> u64 {
+        self.version += 1;
+        let mut storage = self.storage.borrow_mut();
+        let history = storage.histories.entry(key).or_default();
+        let was_present = history.last().is_some_and(|entry| entry.value.is_some());
+        match (was_present, value.is_some()) {
+            (false, true) => self.length += 1,
+            (true, false) => self.length -= 1,
+            _ => {}
+        }
+        history.push(Version {
+            version: self.version,
+            value,
+        });
+        self.version
+    }
+
+    /// Stores `value` under `key` as a new version.
+    /// This operation runs in O(1) amortized time.
+    ///
+    /// # Returns
+    ///
+    /// The version the write was recorded at.
+    pub fn set(&mut self, key: K, value: V) -> u64 {
+        self.write(key, Some(value))
+    }
+
+    /// Removes `key` by recording a tombstone. Earlier versions stay readable.
+    ///
+    /// # Returns
+    ///
+    /// The version the removal was recorded at, or `None` if the key held no value.
+    pub fn remove(&mut self, key: K) -> Option<u64> {
+        if !self.contains_key(&key) {
+            return None;
+        }
+        Some(self.write(key, None))
+    }
+
+    /// Retrieves a copy of the current value of `key`.
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.get_at(key, self.version)
+    }
+
+    /// Returns `true` if `key` currently holds a value.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Retrieves a copy of the value `key` had at `version`.
+    /// This operation runs in O(log h) average time, where h is the number of versions of the key.
+    ///
+    /// # Panics
+    ///
+    /// If `version` is below the watermark of an earlier `collect_garbage`.
+    pub fn get_at(&self, key: &K, version: u64) -> Option<V> {
+        self.storage.borrow().get_at(key, version).cloned()
+    }
+
+    /// Takes a read-only snapshot of the current version. This operation runs in O(log s) time,
+    /// where s is the number of live snapshots; no data is copied.
+    pub fn snapshot(&self) -> Snapshot<K, V> {
+        *self
+            .storage
+            .borrow_mut()
+            .snapshots
+            .entry(self.version)
+            .or_insert(0) += 1;
+        Snapshot {
+            storage: Rc::clone(&self.storage),
+            version: self.version,
+        }
+    }
+
+    /// Drops every version that cannot be seen by a read at or above `watermark`.
+    ///
+    /// For each key, the newest version at or below the watermark is kept, since reads at the
+    /// watermark still need it; everything older goes. A key whose only remaining version is a
+    /// tombstone is removed entirely. The watermark is lowered to the oldest live snapshot, so
+    /// snapshots never lose data.
+    /// This operation runs in O(n + h) time, where h is the total length of all histories.
+    ///
+    /// # Returns
+    ///
+    /// The number of versions dropped.
+    pub fn collect_garbage(&mut self, watermark: u64) -> usize {
+        let mut storage = self.storage.borrow_mut();
+        let oldest_snapshot = storage.snapshots.keys().next().copied();
+        let watermark = watermark
+            .min(self.version)
+            .min(oldest_snapshot.unwrap_or(u64::MAX))
+            .max(storage.oldest_readable);
+
+        let mut dropped = 0;
+        storage.histories.retain(|_, history| {
+            let visible = history.partition_point(|entry| entry.version <= watermark);
+            if visible > 1 {
+                history.drain(..visible - 1);
+                dropped += visible - 1;
+            }
+            if history.len() == 1 && history[0].value.is_none() {
+                dropped += 1;
+                return false;
+            }
+            true
+        });
+        storage.oldest_readable = watermark;
+        dropped
+    }
+
+    /// Returns the total number of versions stored across all keys, tombstones included.
+    pub fn version_count(&self) -> usize {
+        self.storage
+            .borrow()
+            .histories
+            .iter()
+            .map(|(_, history)| history.len())
+            .sum()
+    }
+}
+
+impl<K, V> Snapshot<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    /// Returns the version this snapshot reads at.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Retrieves a copy of the value `key` had when the snapshot was taken.
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.storage.borrow().get_at(key, self.version).cloned()
+    }
+
+    /// Returns every key-value pair as of the snapshot, in no particular order.
+    /// This operation runs in O(n log h) time.
+    pub fn entries(&self) -> Vec<(K, V)> {
+        self.storage
+            .borrow()
+            .histories
+            .iter()
+            .filter_map(|(key, history)| {
+                value_at(history, self.version).map(|value| (key.clone(), value.clone()))
+            })
+            .collect()
+    }
+}
+
+impl<K, V> Drop for Snapshot<K, V> {
+    /// Unregisters the snapshot, so garbage collection may reclaim the versions it pinned.
+    fn drop(&mut self) {
+        let mut storage = self.storage.borrow_mut();
+        if let Some(count) = storage.snapshots.get_mut(&self.version) {
+            *count -= 1;
+            if *count == 0 {
+                storage.snapshots.remove(&self.version);
+            }
+        }
+    }
+}
+
+fn main() {
+    let mut prices = VersionedHashTable::new(0);
+    let v1 = prices.set("apples", 54);
+    prices.set("grapes", 10000);
+    let before_sale = prices.snapshot();
+
+    let v3 = prices.set("apples", 40);
+    prices.remove("grapes");
+    prices.set("oranges", 2);
+
+    println!(
+        "Now at version {} with {} items, empty: {}",
+        prices.version(),
+        prices.len(),
+        prices.is_empty()
+    );
+    println!("Apples now: {:?}", prices.get(&"apples"));
+    println!(
+        "Apples at version {}: {:?}",
+        v1,
+        prices.get_at(&"apples", v1)
+    );
+    println!(
+        "Grapes now: {:?}, contains: {}",
+        prices.get(&"grapes"),
+        prices.contains_key(&"grapes")
+    );
+
+    let mut entries = before_sale.entries();
+    entries.sort();
+    println!(
+        "Snapshot at version {}: {:?}, grapes {:?}",
+        before_sale.version(),
+        entries,
+        before_sale.get(&"grapes")
+    );
+
+    // The snapshot pins version 2, so collecting up to version 4 only goes as far as 2.
+    for _ in 0..5 {
+        prices.set("apples", 45);
+    }
+    let total = prices.version_count();
+    let dropped = prices.collect_garbage(4);
+    println!(
+        "Dropped {} of {} versions while the snapshot is alive",
+        dropped, total
+    );
+    println!(
+        "Apples at version {}: {:?}",
+        v3,
+        prices.get_at(&"apples", v3)
+    );
+
+    drop(before_sale);
+    let dropped = prices.collect_garbage(prices.version());
+    println!(
+        "Dropped {} more once it was released, {} versions left",
+        dropped,
+        prices.version_count()
+    );
+    println!("Apples now: {:?}", prices.get(&"apples"));
+}
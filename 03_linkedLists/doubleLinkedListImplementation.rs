@@ -1,67 +1,493 @@
-use std::fmt::Debug;
+/*
+  A doubly linked list: every node points at both its neighbours, so the list can be walked in
+  either direction and a node can be unlinked in O(1) once you are at it.
+
+  A node is pointed at by two others, which single ownership can't express, so the nodes are
+  managed by hand as in linkedListImplementation.rs: each is a leaked `Box` linked by raw
+  `NonNull` pointers, owned by the list, and freed with `Box::from_raw` exactly once.
+
+  `CursorMut` is the way to edit the middle of the list. It sits on one node, or on a "ghost"
+  position between the tail and the head, and can move either way and insert, remove or splice
+  in whole lists at that spot, each in O(1).
+*/
+
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+type Link<T> = Option<NonNull<Node<T>>>;
 
-#[derive(Clone)]
 struct Node<T> {
     value: T,
-    next: Option<Box<Node<T>>>,
-    prev: Option<Box<Node<T>>>, // Added previous node pointer for a doubly linked list
+    next: Link<T>,
+    prev: Link<T>,
 }
 
 struct DoubleLinkedList<T> {
-    head: Option<Box<Node<T>>>,
-    tail: Option<Box<Node<T>>>,
+    head: Link<T>,
+    tail: Link<T>,
+    length: usize,
+    /// Tells the compiler that the list owns boxed nodes, for drop checking and variance.
+    marker: PhantomData<Box<Node<T>>>,
 }
 
-impl<T: Clone + Debug> DoubleLinkedList<T> {
+// The list owns its values like a `Vec<T>` does and hands out no shared pointers to its nodes,
+// so it can move to or be shared with another thread whenever its values can.
+unsafe impl<T: Send> Send for DoubleLinkedList<T> {}
+unsafe impl<T: Sync> Sync for DoubleLinkedList<T> {}
+
+impl<T> DoubleLinkedList<T> {
     fn new() -> Self {
         DoubleLinkedList {
             head: None,
             tail: None,
+            length: 0,
+            marker: PhantomData,
         }
     }
 
-    fn push_front(&mut self, value: T) {
-        let mut new_node = Box::new(Node {
-            value,
-            next: self.head.clone(),
-            prev: None,
-        });
+    /// Returns the number of values in the list.
+    fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the list holds no values.
+    fn is_empty(&self) -> bool {
+        self.length == 0
+    }
 
-        if let Some(ref mut old_head) = self.head {
-            old_head.prev = Some(new_node.clone());
-        } else {
-            self.tail = Some(new_node.clone());
+    /// Links a new node between `prev` and `next`, which must be adjacent (or the ends).
+    fn insert_between(&mut self, prev: Link<T>, next: Link<T>, value: T) {
+        let node = NonNull::from(Box::leak(Box::new(Node { value, next, prev })));
+        // SAFETY: `prev` and `next` are live nodes owned by the list, and nothing else holds
+        // a reference to them while we relink.
+        unsafe {
+            match prev {
+                Some(mut prev) => prev.as_mut().next = Some(node),
+                None => self.head = Some(node),
+            }
+            match next {
+                Some(mut next) => next.as_mut().prev = Some(node),
+                None => self.tail = Some(node),
+            }
         }
+        self.length += 1;
+    }
+
+    /// Unlinks `node` from the list, frees it and returns its value.
+    ///
+    /// # Safety
+    ///
+    /// `node` must be a live node of this list.
+    unsafe fn unlink(&mut self, node: NonNull<Node<T>>) -> T {
+        let node = Box::from_raw(node.as_ptr());
+        match node.prev {
+            Some(mut prev) => prev.as_mut().next = node.next,
+            None => self.head = node.next,
+        }
+        match node.next {
+            Some(mut next) => next.as_mut().prev = node.prev,
+            None => self.tail = node.prev,
+        }
+        self.length -= 1;
+        node.value
+    }
 
-        self.head = Some(new_node);
+    /// Moves every node of `other` between `prev` and `next`, which must be adjacent (or the
+    /// ends), leaving `other` empty.
+    fn splice_between(&mut self, prev: Link<T>, next: Link<T>, other: &mut Self) {
+        let (Some(mut first), Some(mut last)) = (other.head.take(), other.tail.take()) else {
+            return;
+        };
+        // SAFETY: all four nodes are live. `other` has been emptied, so its nodes are now owned
+        // by this list alone.
+        unsafe {
+            first.as_mut().prev = prev;
+            last.as_mut().next = next;
+            match prev {
+                Some(mut prev) => prev.as_mut().next = Some(first),
+                None => self.head = Some(first),
+            }
+            match next {
+                Some(mut next) => next.as_mut().prev = Some(last),
+                None => self.tail = Some(last),
+            }
+        }
+        self.length += std::mem::take(&mut other.length);
     }
 
+    /// Adds a value at the front of the list.
+    /// This operation runs in O(1) time.
+    fn push_front(&mut self, value: T) {
+        self.insert_between(None, self.head, value);
+    }
+
+    /// Adds a value at the end of the list.
+    /// This operation runs in O(1) time.
     fn push_back(&mut self, value: T) {
-        let mut new_node = Box::new(Node {
-            value,
-            next: None,
-            prev: self.tail.clone(),
-        });
+        self.insert_between(self.tail, None, value);
+    }
+
+    /// Removes and returns the first value, or `None` if the list is empty.
+    /// This operation runs in O(1) time.
+    fn pop_front(&mut self) -> Option<T> {
+        // SAFETY: the head is a live node of this list.
+        self.head.map(|head| unsafe { self.unlink(head) })
+    }
+
+    /// Removes and returns the last value, or `None` if the list is empty.
+    /// This operation runs in O(1) time.
+    fn pop_back(&mut self) -> Option<T> {
+        // SAFETY: the tail is a live node of this list.
+        self.tail.map(|tail| unsafe { self.unlink(tail) })
+    }
+
+    /// Returns a reference to the first value.
+    fn front(&self) -> Option<&T> {
+        // SAFETY: the head is live, and the reference borrows the list.
+        self.head.map(|head| unsafe { &(*head.as_ptr()).value })
+    }
+
+    /// Returns a reference to the last value.
+    fn back(&self) -> Option<&T> {
+        // SAFETY: the tail is live, and the reference borrows the list.
+        self.tail.map(|tail| unsafe { &(*tail.as_ptr()).value })
+    }
+
+    /// Returns a double-ended iterator over references to the values.
+    fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            front: self.head,
+            back: self.tail,
+            remaining: self.length,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a double-ended iterator over mutable references to the values.
+    fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            front: self.head,
+            back: self.tail,
+            remaining: self.length,
+            marker: PhantomData,
+        }
+    }
 
-        if let Some(ref mut old_tail) = self.tail {
-            old_tail.next = Some(new_node.clone());
-        } else {
-            self.head = Some(new_node.clone());
+    /// Returns a cursor on the first value, or on the ghost position if the list is empty.
+    fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: self.head,
+            index: 0,
+            list: self,
         }
+    }
 
-        self.tail = Some(new_node);
+    /// Returns a cursor on the last value, or on the ghost position if the list is empty.
+    fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: self.tail,
+            index: self.length.saturating_sub(1),
+            list: self,
+        }
     }
 
-    fn print_list(&self) {
-        let mut elements = Vec::new();
-        let mut cursor = self.head.clone();
+    fn print_list(&self)
+    where
+        T: Debug,
+    {
+        println!("{:?}", self);
+    }
+}
+
+impl<T> Drop for DoubleLinkedList<T> {
+    /// Frees the nodes one by one in a loop, so long lists can't overflow the stack.
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+/// A position in a `DoubleLinkedList` from which the list can be edited in O(1).
+///
+/// The cursor is either on a value, or on the ghost position that sits between the tail and
+/// the head (the only position of an empty list). Moving past either end lands on the ghost,
+/// and moving on from the ghost wraps around to the other end.
+struct CursorMut<'a, T> {
+    list: &'a mut DoubleLinkedList<T>,
+    /// The node under the cursor, or `None` on the ghost.
+    current: Link<T>,
+    /// The index of the current value; the length of the list on the ghost.
+    index: usize,
+}
 
-        while let Some(node) = cursor {
-            elements.push(node.value);
-            cursor = node.next.clone();
+impl<'a, T> CursorMut<'a, T> {
+    /// Returns the index of the current value, or `None` on the ghost.
+    fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    /// Returns a mutable reference to the current value, or `None` on the ghost.
+    fn current(&mut self) -> Option<&mut T> {
+        // SAFETY: the node is live, and the reference borrows the cursor mutably, which borrows
+        // the list mutably.
+        self.current
+            .map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Moves to the next value, from the tail to the ghost, or from the ghost to the head.
+    fn move_next(&mut self) {
+        match self.current {
+            Some(node) => {
+                // SAFETY: the current node is live.
+                self.current = unsafe { node.as_ref().next };
+                self.index += 1;
+            }
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
         }
+    }
 
-        println!("{:?}", elements);
+    /// Moves to the previous value, from the head to the ghost, or from the ghost to the tail.
+    fn move_prev(&mut self) {
+        match self.current {
+            Some(node) => {
+                // SAFETY: the current node is live.
+                self.current = unsafe { node.as_ref().prev };
+                self.index = match self.current {
+                    Some(_) => self.index - 1,
+                    None => self.list.length,
+                };
+            }
+            None => {
+                self.current = self.list.tail;
+                self.index = self.list.length.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Returns the nodes before and after the current position, as `insert_before` and
+    /// `insert_after` see it. On the ghost, "before" is the tail and "after" is the head.
+    fn neighbours(&self) -> (Link<T>, Link<T>) {
+        match self.current {
+            // SAFETY: the current node is live.
+            Some(node) => unsafe { (node.as_ref().prev, node.as_ref().next) },
+            None => (self.list.tail, self.list.head),
+        }
+    }
+
+    /// Inserts a value just before the cursor, or at the back of the list on the ghost.
+    /// The cursor stays on the same value.
+    fn insert_before(&mut self, value: T) {
+        let (prev, _) = self.neighbours();
+        self.list.insert_between(prev, self.current, value);
+        self.index += 1;
+    }
+
+    /// Inserts a value just after the cursor, or at the front of the list on the ghost.
+    /// The cursor stays on the same value.
+    fn insert_after(&mut self, value: T) {
+        let (_, next) = self.neighbours();
+        self.list.insert_between(self.current, next, value);
+        if self.current.is_none() {
+            self.index += 1;
+        }
+    }
+
+    /// Removes the current value and moves the cursor to the next one.
+    ///
+    /// # Returns
+    ///
+    /// The removed value, or `None` on the ghost, where nothing is removed.
+    fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        // SAFETY: the current node is a live node of the list, and the cursor moves off it
+        // before it is freed.
+        unsafe {
+            self.current = node.as_ref().next;
+            Some(self.list.unlink(node))
+        }
+    }
+
+    /// Moves every value of `other` into the list just after the cursor, or at the front on
+    /// the ghost, leaving `other` empty. This operation runs in O(1) time.
+    fn splice_after(&mut self, other: &mut DoubleLinkedList<T>) {
+        let (_, next) = self.neighbours();
+        let added = other.length;
+        self.list.splice_between(self.current, next, other);
+        if self.current.is_none() {
+            self.index += added;
+        }
+    }
+
+    /// Moves every value of `other` into the list just before the cursor, or at the back on
+    /// the ghost, leaving `other` empty. This operation runs in O(1) time.
+    fn splice_before(&mut self, other: &mut DoubleLinkedList<T>) {
+        let (prev, _) = self.neighbours();
+        self.index += other.length;
+        self.list.splice_between(prev, self.current, other);
+    }
+}
+
+/// A double-ended iterator over references to the values of a `DoubleLinkedList`.
+struct Iter<'a, T> {
+    front: Link<T>,
+    back: Link<T>,
+    /// The values not yet returned from either end; the ends meet when it reaches zero.
+    remaining: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        // SAFETY: the iterator borrows the list for 'a, so its nodes stay alive and unchanged,
+        // and `front` is a node not yet returned.
+        let node = unsafe { &*self.front?.as_ptr() };
+        self.front = node.next;
+        self.remaining -= 1;
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        // SAFETY: as in `next`, from the other end.
+        let node = unsafe { &*self.back?.as_ptr() };
+        self.back = node.prev;
+        self.remaining -= 1;
+        Some(&node.value)
+    }
+}
+
+/// A double-ended iterator over mutable references to the values of a `DoubleLinkedList`.
+struct IterMut<'a, T> {
+    front: Link<T>,
+    back: Link<T>,
+    remaining: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        // SAFETY: the iterator borrows the list mutably for 'a, and `remaining` stops the two
+        // ends before they meet, so each value is handed out once and references never overlap.
+        let node = unsafe { &mut *self.front?.as_ptr() };
+        self.front = node.next;
+        self.remaining -= 1;
+        Some(&mut node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        // SAFETY: as in `next`, from the other end.
+        let node = unsafe { &mut *self.back?.as_ptr() };
+        self.back = node.prev;
+        self.remaining -= 1;
+        Some(&mut node.value)
+    }
+}
+
+/// A double-ended iterator that moves the values out of a `DoubleLinkedList`.
+struct IntoIter<T> {
+    list: DoubleLinkedList<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len(), Some(self.list.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> IntoIterator for DoubleLinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a DoubleLinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut DoubleLinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> FromIterator<T> for DoubleLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = DoubleLinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for DoubleLinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push_back(value);
+        }
+    }
+}
+
+impl<T: Debug> Debug for DoubleLinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
     }
 }
 
@@ -73,4 +499,85 @@ fn main() {
 
     println!("List elements:");
     list.print_list();
+    println!(
+        "Front {:?}, back {:?}, {} values, backwards {:?}",
+        list.front(),
+        list.back(),
+        list.len(),
+        list.iter().rev().collect::<Vec<_>>()
+    );
+    println!(
+        "pop_front {:?}, pop_back {:?}, left {:?}, empty {}",
+        list.pop_front(),
+        list.pop_back(),
+        list,
+        list.is_empty()
+    );
+
+    // Edit the middle of a list with a cursor.
+    let mut letters: DoubleLinkedList<char> = "abcdef".chars().collect();
+    let mut cursor = letters.cursor_front_mut();
+    cursor.move_next();
+    cursor.move_next();
+    println!(
+        "Cursor at {:?}: {:?}",
+        cursor.index(),
+        cursor.current().copied()
+    );
+    if let Some(letter) = cursor.current() {
+        *letter = 'C';
+    }
+    cursor.insert_before('x');
+    cursor.insert_after('y');
+    println!(
+        "Removed {:?}, now at {:?}",
+        cursor.remove_current(),
+        cursor.current().copied()
+    );
+    let mut digits: DoubleLinkedList<char> = "123".chars().collect();
+    cursor.splice_before(&mut digits);
+    cursor.move_prev();
+    println!(
+        "After splicing digits before it, stepped back to {:?} at {:?}",
+        cursor.current().copied(),
+        cursor.index()
+    );
+    let mut tail: DoubleLinkedList<char> = "zz".chars().collect();
+    let mut back = letters.cursor_back_mut();
+    back.splice_after(&mut tail);
+    back.move_next();
+    back.move_next();
+    back.move_next();
+    println!("Past the end: {:?}", back.current());
+    back.insert_after('<');
+    back.insert_before('>');
+    println!(
+        "{:?}, digits {:?}, zz {:?}",
+        letters,
+        digits,
+        tail.is_empty()
+    );
+
+    let mut numbers: DoubleLinkedList<i32> = (1..=6).collect();
+    for value in &mut numbers {
+        *value *= 10;
+    }
+    if let Some(last) = numbers.iter_mut().next_back() {
+        *last = 0;
+    }
+    let mut both_ends = numbers.iter();
+    println!(
+        "Ends meet: {:?} {:?} {:?} {:?}, then {:?} left",
+        both_ends.next(),
+        both_ends.next_back(),
+        both_ends.next(),
+        both_ends.next_back(),
+        both_ends.collect::<Vec<_>>()
+    );
+    let total: i32 = (&numbers).into_iter().sum();
+    let reversed: Vec<i32> = numbers.into_iter().rev().collect();
+    println!("Sum {}, reversed {:?}", total, reversed);
+
+    let long: DoubleLinkedList<u32> = (0..1_000_000).collect();
+    println!("A list of {} nodes dropped without recursion", long.len());
 }
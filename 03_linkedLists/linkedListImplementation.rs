@@ -1,149 +1,552 @@
-use std::fmt::Debug;
+/*
+  A singly linked list with O(1) append.
 
-#[derive(Clone)]
-struct Node<T> {
-    value: T,
-    next: Option<Box<Node<T>>>,
+  Appending in O(1) needs a pointer to the last node, but that node is already owned by the
+  one before it. Rust's ownership can't express "owned by the previous node, and also pointed
+  to by the list", so the list manages the nodes itself:
+
+  - Every node is allocated with `Box::new` and turned into a raw `NonNull` pointer with
+    `Box::leak`. From then on, nodes are linked by raw pointers only; no `Box` or `&mut` to a
+    node outlives a single operation, so `tail` can never be invalidated behind our back.
+  - The list owns every node reachable from `head`, and `tail` points at the last of them.
+  - A node is freed with `Box::from_raw` exactly once, when it is removed or the list is
+    dropped, so nothing leaks.
+*/
+
+use std::error::Error;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+pub(crate) struct Node<T> {
+    pub(crate) value: T,
+    pub(crate) next: Option<NonNull<Node<T>>>,
+}
+
+pub(crate) struct LinkedList<T> {
+    pub(crate) head: Option<NonNull<Node<T>>>,
+    /// The last node, so push_back doesn't have to walk the list.
+    pub(crate) tail: Option<NonNull<Node<T>>>,
+    pub(crate) length: usize,
+    /// Tells the compiler that the list owns boxed nodes, for drop checking and variance.
+    marker: PhantomData<Box<Node<T>>>,
 }
 
-struct LinkedList<T> {
-    head: Option<Box<Node<T>>>,
-    tail: Option<*mut Node<T>>, // Using raw pointer for mutability
+// The list owns its values like a `Vec<T>` does and hands out no shared pointers to its nodes,
+// so it can move to or be shared with another thread whenever its values can.
+unsafe impl<T: Send> Send for LinkedList<T> {}
+unsafe impl<T: Sync> Sync for LinkedList<T> {}
+
+/// The error returned by `LinkedList::insert` and `LinkedList::remove` for an index past the
+/// end of the list.
+#[derive(Debug, PartialEq)]
+pub(crate) struct IndexOutOfBounds {
+    index: usize,
     length: usize,
 }
 
-impl<T: Clone + Debug> LinkedList<T> {
-    fn new(value: T) -> Self {
-        let new_node = Box::new(Node { value, next: None });
-        let raw_node: *mut _ = Box::into_raw(new_node.clone());
+impl fmt::Display for IndexOutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "index {} is out of bounds for a list of length {}",
+            self.index, self.length
+        )
+    }
+}
+
+impl Error for IndexOutOfBounds {}
 
+impl<T> LinkedList<T> {
+    /// Creates an empty list.
+    pub(crate) fn new() -> Self {
         LinkedList {
-            head: Some(new_node),
-            tail: Some(raw_node),
-            length: 1,
+            head: None,
+            tail: None,
+            length: 0,
+            marker: PhantomData,
         }
     }
 
-    fn append(&mut self, value: T) {
-        let mut new_node = Box::new(Node { value, next: None });
-        let raw_node: *mut _ = &mut *new_node;
+    /// Returns the number of values in the list.
+    pub(crate) fn len(&self) -> usize {
+        self.length
+    }
 
-        if let Some(tail) = self.tail {
-            unsafe {
-                (*tail).next = Some(new_node);
-            }
-        } else {
-            self.head = Some(new_node.clone());
+    /// Returns `true` if the list holds no values.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Allocates a node that the list will own through a raw pointer.
+    fn new_node(value: T, next: Option<NonNull<Node<T>>>) -> NonNull<Node<T>> {
+        NonNull::from(Box::leak(Box::new(Node { value, next })))
+    }
+
+    /// Returns the node at `index`, which must be less than the length.
+    pub(crate) fn node_at(&self, index: usize) -> NonNull<Node<T>> {
+        let mut current = self.head.expect("index within the list");
+        for _ in 0..index {
+            // SAFETY: `current` is one of our live nodes, and index < length means it has a
+            // successor.
+            current = unsafe { current.as_ref().next }.expect("index within the list");
+        }
+        current
+    }
+
+    /// Adds a value at the end of the list.
+    /// This operation runs in O(1) time.
+    pub(crate) fn push_back(&mut self, value: T) {
+        let new_node = Self::new_node(value, None);
+
+        match self.tail {
+            // SAFETY: the tail is a live node owned by the list, and no other reference to it
+            // exists during this call.
+            Some(mut tail) => unsafe { tail.as_mut().next = Some(new_node) },
+            None => self.head = Some(new_node),
         }
 
-        self.tail = Some(raw_node);
+        self.tail = Some(new_node);
         self.length += 1;
     }
 
-    fn prepend(&mut self, value: T) {
-        let mut new_node = Box::new(Node {
-            value,
-            next: self.head.take(),
-        });
-
-        let raw_node: *mut _ = &mut *new_node;
+    /// Adds a value at the front of the list.
+    /// This operation runs in O(1) time.
+    pub(crate) fn push_front(&mut self, value: T) {
+        let new_node = Self::new_node(value, self.head);
         self.head = Some(new_node);
 
         if self.tail.is_none() {
-            self.tail = Some(raw_node);
+            self.tail = Some(new_node);
         }
 
         self.length += 1;
     }
 
-    fn insert(&mut self, index: usize, value: T) {
-        if index >= self.length {
-            return self.append(value);
+    /// Removes and returns the first value, or `None` if the list is empty.
+    /// This operation runs in O(1) time.
+    pub(crate) fn pop_front(&mut self) -> Option<T> {
+        let head = self.head?;
+        // SAFETY: `head` came from `Box::leak` and is unlinked from the list right here, so it
+        // is freed exactly once.
+        let node = unsafe { Box::from_raw(head.as_ptr()) };
+        self.head = node.next;
+        if self.head.is_none() {
+            self.tail = None;
         }
+        self.length -= 1;
+        Some(node.value)
+    }
 
-        if index == 0 {
-            return self.prepend(value);
+    /// Removes and returns the last value, or `None` if the list is empty.
+    /// A singly linked list can't step back from the tail, so this walks the whole list and
+    /// runs in O(n) time.
+    pub(crate) fn pop_back(&mut self) -> Option<T> {
+        if self.length <= 1 {
+            return self.pop_front();
         }
+        let mut new_tail = self.node_at(self.length - 2);
+        // SAFETY: `new_tail` and its successor, the old tail, are live nodes owned by the list.
+        // The old tail is unlinked before it is freed.
+        let node = unsafe {
+            let old_tail = new_tail.as_mut().next.take().expect("the tail follows");
+            Box::from_raw(old_tail.as_ptr())
+        };
+        self.tail = Some(new_tail);
+        self.length -= 1;
+        Some(node.value)
+    }
 
-        let mut current = self.head.as_mut();
-        for _ in 0..index - 1 {
-            if let Some(current_node) = current {
-                current = current_node.next.as_mut();
-            } else {
-                return;
-            }
+    /// Inserts a value so that it ends up at position `index`, shifting later values back.
+    /// This operation runs in O(index) time.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if `index` is greater than the length, leaving the list unchanged.
+    pub(crate) fn insert(&mut self, index: usize, value: T) -> Result<(), IndexOutOfBounds> {
+        if index > self.length {
+            return Err(IndexOutOfBounds {
+                index,
+                length: self.length,
+            });
         }
 
-        if let Some(current_node) = current {
-            let mut new_node = Box::new(Node {
-                value,
-                next: current_node.next.take(),
-            });
-            current_node.next = Some(new_node);
+        if index == 0 {
+            self.push_front(value);
+        } else if index == self.length {
+            self.push_back(value);
+        } else {
+            let mut previous = self.node_at(index - 1);
+            // SAFETY: `previous` is a live node and not the tail, since index < length, so the
+            // new node goes between two existing nodes and `tail` stays valid.
+            unsafe {
+                let new_node = Self::new_node(value, previous.as_ref().next);
+                previous.as_mut().next = Some(new_node);
+            }
             self.length += 1;
         }
+        Ok(())
     }
 
-    fn remove(&mut self, index: usize) {
+    /// Removes and returns the value at position `index`.
+    /// This operation runs in O(index) time.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if `index` is not less than the length, leaving the list unchanged.
+    pub(crate) fn remove(&mut self, index: usize) -> Result<T, IndexOutOfBounds> {
         if index >= self.length {
-            return;
+            return Err(IndexOutOfBounds {
+                index,
+                length: self.length,
+            });
         }
 
         if index == 0 {
-            if let Some(mut head_node) = self.head.take() {
-                self.head = head_node.next.take();
+            return Ok(self.pop_front().expect("a non-empty list has a head"));
+        }
 
-                if self.length == 1 {
-                    self.tail = None;
-                }
-                self.length -= 1;
-                return;
+        let mut previous = self.node_at(index - 1);
+        // SAFETY: `previous` and its successor are live nodes owned by the list. The removed
+        // node is unlinked before it is freed, and the tail moves back if it was the tail.
+        let node = unsafe {
+            let removed = previous.as_ref().next.expect("index within the list");
+            previous.as_mut().next = removed.as_ref().next;
+            if self.tail == Some(removed) {
+                self.tail = Some(previous);
             }
+            Box::from_raw(removed.as_ptr())
+        };
+        self.length -= 1;
+        Ok(node.value)
+    }
+
+    /// Returns a reference to the value at position `index`, or `None` if it is out of bounds.
+    /// This operation runs in O(index) time.
+    pub(crate) fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.length {
+            return None;
         }
+        // SAFETY: the node is live, and the returned reference borrows the list.
+        Some(unsafe { &(*self.node_at(index).as_ptr()).value })
+    }
 
-        let mut current = self.head.as_mut();
-        for _ in 0..index - 1 {
-            if let Some(current_node) = current {
-                current = current_node.next.as_mut();
-            } else {
-                return;
-            }
+    /// Returns a mutable reference to the value at position `index`, or `None` if it is out of
+    /// bounds.
+    pub(crate) fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.length {
+            return None;
+        }
+        // SAFETY: the node is live, and the returned reference borrows the list mutably, so
+        // it is the only reference to the value.
+        Some(unsafe { &mut (*self.node_at(index).as_ptr()).value })
+    }
+
+    /// Returns `true` if the list holds a value equal to `value`.
+    /// This operation runs in O(n) time.
+    pub(crate) fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|v| v == value)
+    }
+
+    /// Splits the list in two at `at`. `self` keeps the values before `at`, and the values from
+    /// `at` on are returned as a new list.
+    /// This operation runs in O(at) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is greater than the length.
+    pub(crate) fn split_off(&mut self, at: usize) -> LinkedList<T> {
+        assert!(at <= self.length, "cannot split off at a nonexistent index");
+        if at == 0 {
+            return std::mem::replace(self, LinkedList::new());
+        }
+
+        let mut last_kept = self.node_at(at - 1);
+        let mut rest = LinkedList::new();
+        // SAFETY: `last_kept` is a live node. Cutting its link hands every node after it to
+        // `rest`, so each node is still owned by exactly one list.
+        rest.head = unsafe { last_kept.as_mut().next.take() };
+        if rest.head.is_some() {
+            rest.tail = self.tail;
         }
+        rest.length = self.length - at;
+        self.tail = Some(last_kept);
+        self.length = at;
+        rest
+    }
 
-        if let Some(current_node) = current {
-            let mut node_to_remove = current_node.next.take();
-            if let Some(ref mut next_node) = node_to_remove {
-                current_node.next = next_node.next.take();
+    /// Moves all values of `other` to the end of this list, leaving `other` empty.
+    /// This operation runs in O(1) time.
+    pub(crate) fn append(&mut self, other: &mut LinkedList<T>) {
+        let Some(other_head) = other.head.take() else {
+            return;
+        };
+        match self.tail {
+            // SAFETY: the tail is a live node. Linking it to `other`'s nodes transfers their
+            // ownership, and `other` is emptied so it no longer frees them.
+            Some(mut tail) => unsafe { tail.as_mut().next = Some(other_head) },
+            None => self.head = Some(other_head),
+        }
+        self.tail = other.tail.take();
+        self.length += std::mem::take(&mut other.length);
+    }
 
-                if index == self.length - 1 {
-                    self.tail = Some(Box::into_raw(next_node.clone()));
+    /// Keeps only the values for which `keep` returns `true`, in order, freeing the rest.
+    /// This operation runs in O(n) time.
+    pub(crate) fn retain(&mut self, mut keep: impl FnMut(&T) -> bool) {
+        let mut previous: Option<NonNull<Node<T>>> = None;
+        let mut current = self.head;
+
+        while let Some(node) = current {
+            // SAFETY: `node` is a live node owned by the list. A node that is dropped is first
+            // unlinked from its predecessor (or the head) and then freed exactly once.
+            unsafe {
+                current = node.as_ref().next;
+                if keep(&node.as_ref().value) {
+                    previous = Some(node);
+                    continue;
                 }
+                match previous {
+                    Some(mut previous) => previous.as_mut().next = current,
+                    None => self.head = current,
+                }
+                drop(Box::from_raw(node.as_ptr()));
             }
-
             self.length -= 1;
         }
+        self.tail = previous;
     }
 
-    fn to_vec(&self) -> Vec<T> {
-        let mut result = Vec::new();
-        let mut current = &self.head;
+    /// Returns an iterator over references to the values, front to back.
+    pub(crate) fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head,
+            remaining: self.length,
+            marker: PhantomData,
+        }
+    }
 
-        while let Some(ref node) = current {
-            result.push(node.value.clone());
-            current = &node.next;
+    /// Returns an iterator over mutable references to the values, front to back.
+    pub(crate) fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            next: self.head,
+            remaining: self.length,
+            marker: PhantomData,
         }
+    }
 
-        result
+    pub(crate) fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.iter().cloned().collect()
     }
 }
 
+impl<T> Drop for LinkedList<T> {
+    /// Frees the nodes one by one, front to back. A loop rather than recursion, so even very
+    /// long lists can't overflow the stack.
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+/// An iterator over references to the values of a `LinkedList`, returned by `iter`.
+pub(crate) struct Iter<'a, T> {
+    next: Option<NonNull<Node<T>>>,
+    remaining: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.next?;
+        // SAFETY: the iterator borrows the list for 'a, so its nodes stay alive and unchanged.
+        let node = unsafe { &*node.as_ptr() };
+        self.next = node.next;
+        self.remaining -= 1;
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// An iterator over mutable references to the values of a `LinkedList`, returned by `iter_mut`.
+pub(crate) struct IterMut<'a, T> {
+    next: Option<NonNull<Node<T>>>,
+    remaining: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        let node = self.next?;
+        // SAFETY: the iterator borrows the list mutably for 'a and visits each node once, so
+        // the references it hands out never overlap.
+        let node = unsafe { &mut *node.as_ptr() };
+        self.next = node.next;
+        self.remaining -= 1;
+        Some(&mut node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// An iterator that moves the values out of a `LinkedList`, returned by `into_iter`.
+pub(crate) struct IntoIter<T> {
+    list: LinkedList<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len(), Some(self.list.len()))
+    }
+}
+
+impl<T> IntoIterator for LinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = LinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push_back(value);
+        }
+    }
+}
+
+impl<T: Debug> Debug for LinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Checks the list from another thread, which needs `LinkedList<T>: Send + Sync`.
+fn print_from_thread<T: Debug + Sync>(list: &LinkedList<T>) {
+    std::thread::scope(|scope| {
+        scope.spawn(|| println!("From another thread: {:?}", list));
+    });
+}
+
 fn main() {
-    let mut my_linked_list = LinkedList::new(10);
-    my_linked_list.append(5);
-    my_linked_list.append(16);
-    my_linked_list.prepend(1);
-    my_linked_list.insert(2, 99);
-    my_linked_list.insert(20, 88);
-    my_linked_list.remove(2);
+    let mut my_linked_list = LinkedList::new();
+    my_linked_list.push_back(10);
+    my_linked_list.push_back(5);
+    my_linked_list.push_back(16);
+    my_linked_list.push_front(1);
+    my_linked_list.insert(2, 99).unwrap();
+    println!("Insert at 20: {:?}", my_linked_list.insert(20, 88));
+    my_linked_list.insert(5, 88).unwrap();
+    println!("Removed {:?}", my_linked_list.remove(2));
     println!("{:?}", my_linked_list.to_vec());
+    if let Err(err) = my_linked_list.remove(9) {
+        println!("error: {}", err);
+    }
+
+    // Removing the last node moves the tail back, so appending afterwards still works.
+    my_linked_list.remove(4).unwrap();
+    my_linked_list.push_back(42);
+    println!(
+        "{:?}, length {}, empty {}",
+        my_linked_list,
+        my_linked_list.len(),
+        my_linked_list.is_empty()
+    );
+    print_from_thread(&my_linked_list);
+
+    for value in &mut my_linked_list {
+        *value *= 10;
+    }
+    if let Some(first) = my_linked_list.get_mut(0) {
+        *first = 0;
+    }
+    println!(
+        "Scaled: {:?}, get(2) = {:?}, get(9) = {:?}, contains 160: {}",
+        my_linked_list,
+        my_linked_list.get(2),
+        my_linked_list.get(9),
+        my_linked_list.contains(&160)
+    );
+    println!(
+        "pop_front {:?}, pop_back {:?}, left {:?}",
+        my_linked_list.pop_front(),
+        my_linked_list.pop_back(),
+        my_linked_list
+    );
+
+    let mut numbers: LinkedList<i32> = (1..=10).collect();
+    let mut upper = numbers.split_off(6);
+    println!("Split: {:?} and {:?}", numbers, upper);
+    numbers.retain(|n| n % 2 == 0);
+    numbers.extend([100, 200]);
+    numbers.append(&mut upper);
+    println!(
+        "Evens, extended and appended: {:?}, other now {:?}",
+        numbers, upper
+    );
+    let total: i32 = (&numbers).into_iter().sum();
+    let doubled: Vec<i32> = numbers.into_iter().map(|n| n * 2).collect();
+    println!("Sum {}, doubled {:?}", total, doubled);
+
+    // Dropping a long list frees its nodes in a loop rather than recursing into each one.
+    let long: LinkedList<u32> = (0..1_000_000).collect();
+    println!(
+        "A list of {} nodes dropped without overflowing the stack",
+        long.len()
+    );
+    drop(long);
 }
@@ -1,84 +1,515 @@
-#[derive(Clone)]
-struct Node<T> {
-    value: T,
-    next: Option<Box<Node<T>>>,
+/*
+  Linked-list algorithms that rewire the nodes in place, without copying values or allocating:
+  reversing all or part of the list, merging and sorting, partitioning, rotating, and the
+  two-pointer tricks for finding the middle or the n-th node from the end.
+
+  The algorithms are extra methods on the list from linkedListImplementation.rs, working
+  directly on its nodes. Every algorithm keeps `tail` up to date as it goes, rather than
+  walking the list again at the end to find the last node.
+*/
+
+use std::ptr::NonNull;
+
+#[allow(dead_code)]
+#[path = "linkedListImplementation.rs"]
+mod linked_list;
+
+use linked_list::{LinkedList, Node};
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+/// Returns the node after `node`.
+///
+/// # Safety
+///
+/// `node` must be a live node of a list the caller has exclusive access to. The same goes for
+/// the other raw-link helpers below.
+unsafe fn next<T>(node: NonNull<Node<T>>) -> Link<T> {
+    (*node.as_ptr()).next
 }
 
-struct LinkedList<T> {
-    head: Option<Box<Node<T>>>,
-    tail: Option<*mut Node<T>>, // Using raw pointer for mutability
+/// Points `node` at `next`.
+unsafe fn set_next<T>(node: NonNull<Node<T>>, next: Link<T>) {
+    (*node.as_ptr()).next = next;
+}
+
+/// Returns a reference to the value of `node`, for as long as the caller's borrow of the list.
+unsafe fn value<'a, T>(node: NonNull<Node<T>>) -> &'a T {
+    &(*node.as_ptr()).value
+}
+
+/// Reverses the `count` nodes starting at `start`, which must all exist.
+///
+/// # Returns
+///
+/// The new first node, the new last node (`start`, whose link is left dangling for the
+/// caller to set) and the node that followed the run.
+unsafe fn reverse_run<T>(
+    start: NonNull<Node<T>>,
+    count: usize,
+) -> (NonNull<Node<T>>, NonNull<Node<T>>, Link<T>) {
+    let mut prev = None;
+    let mut current = Some(start);
+    for _ in 0..count {
+        let node = current.expect("the run is within the list");
+        current = next(node);
+        set_next(node, prev);
+        prev = Some(node);
+    }
+    (prev.expect("the run is not empty"), start, current)
+}
+
+/// Cuts the chain starting at `start` after `count` nodes and returns the rest, or `None` if
+/// the chain is no longer than that.
+unsafe fn cut<T>(start: NonNull<Node<T>>, count: usize) -> Link<T> {
+    let mut last = start;
+    for _ in 1..count {
+        match next(last) {
+            Some(node) => last = node,
+            None => return None,
+        }
+    }
+    let rest = next(last);
+    set_next(last, None);
+    rest
+}
+
+/// Merges two sorted, `None`-terminated chains into one, stably: of equal values, those from
+/// `a` come first.
+///
+/// # Returns
+///
+/// The head and the tail of the merged chain.
+unsafe fn merge_chains<T: Ord>(mut a: Link<T>, mut b: Link<T>) -> (Link<T>, Link<T>) {
+    let (mut head, mut tail): (Link<T>, Link<T>) = (None, None);
+    loop {
+        let node = match (a, b) {
+            (Some(x), Some(y)) if value(y) < value(x) => {
+                b = next(y);
+                y
+            }
+            (Some(x), _) => {
+                a = next(x);
+                x
+            }
+            (None, Some(y)) => {
+                b = next(y);
+                y
+            }
+            (None, None) => break,
+        };
+        match tail {
+            Some(tail) => set_next(tail, Some(node)),
+            None => head = Some(node),
+        }
+        tail = Some(node);
+    }
+    if let Some(tail) = tail {
+        set_next(tail, None);
+    }
+    (head, tail)
 }
 
 impl<T> LinkedList<T> {
-    fn new() -> Self {
-        LinkedList {
-            head: None,
-            tail: None,
+    fn reverse(&mut self) {
+        let mut prev = None;
+        let mut current = self.head;
+
+        while let Some(current_node) = current {
+            // SAFETY: every node reachable from `head` is live and owned by the list, and we
+            // hold `&mut self`, so nothing else can see the links while they are rewired.
+            unsafe {
+                current = next(current_node);
+                set_next(current_node, prev);
+            }
+            prev = Some(current_node);
         }
+
+        // The old head is the new tail, so there is no need to walk the list again.
+        self.tail = self.head;
+        self.head = prev;
     }
 
-    fn push(&mut self, value: T) {
-        let mut new_node = Box::new(Node { value, next: None });
+    /// Reverses the list by recursion: reverse everything after the head, then hang the old
+    /// head off the end. Uses O(n) stack, so prefer `reverse` for long lists.
+    fn reverse_recursive(&mut self) {
+        // SAFETY: `node` and everything after it are live nodes of this list.
+        unsafe fn reverse_from<T>(node: NonNull<Node<T>>) -> NonNull<Node<T>> {
+            let Some(rest) = next(node) else {
+                return node;
+            };
+            let new_head = reverse_from(rest);
+            // `rest` is now the last node of the reversed remainder.
+            set_next(rest, Some(node));
+            set_next(node, None);
+            new_head
+        }
 
-        let raw_node: *mut _ = &mut *new_node;
+        if let Some(head) = self.head {
+            // SAFETY: `head` starts the chain of nodes this list owns.
+            self.head = Some(unsafe { reverse_from(head) });
+            self.tail = Some(head);
+        }
+    }
 
-        if self.tail.is_some() {
+    /// Reverses the values at positions `left` through `right`, inclusive, counting from 0.
+    /// This operation runs in O(right) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `left > right` or `right` is not less than the length.
+    fn reverse_between(&mut self, left: usize, right: usize) {
+        assert!(
+            left <= right && right < self.length,
+            "the range must lie within the list"
+        );
+        // SAFETY: every node from position `left - 1` to `right + 1` is live and owned by the
+        // list, and the run is relinked to the nodes on either side before we return.
+        unsafe {
+            let before = left.checked_sub(1).map(|index| self.node_at(index));
+            let start = match before {
+                Some(before) => next(before),
+                None => self.head,
+            }
+            .expect("index within the list");
+            let (first, last, after) = reverse_run(start, right - left + 1);
+            set_next(last, after);
+            match before {
+                Some(before) => set_next(before, Some(first)),
+                None => self.head = Some(first),
+            }
+            if after.is_none() {
+                self.tail = Some(last);
+            }
+        }
+    }
+
+    /// Reverses each consecutive group of `k` values. A shorter group left at the end keeps its
+    /// order. This operation runs in O(n) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is zero.
+    fn reverse_k_group(&mut self, k: usize) {
+        assert!(k > 0, "groups must hold at least one value");
+        let mut previous_last: Link<T> = None;
+        let mut current = self.head;
+        let mut remaining = self.length;
+
+        while remaining >= k {
+            // SAFETY: at least `k` live nodes start at `current`. Each reversed group is linked
+            // to the one before it and the rest of the list before the next group starts.
             unsafe {
-                (*self.tail.unwrap()).next = Some(new_node);
+                let (first, last, after) =
+                    reverse_run(current.expect("remaining counts live nodes"), k);
+                set_next(last, after);
+                match previous_last {
+                    Some(previous) => set_next(previous, Some(first)),
+                    None => self.head = Some(first),
+                }
+                previous_last = Some(last);
+                current = after;
             }
-        } else {
-            self.head = Some(new_node);
+            remaining -= k;
         }
 
-        self.tail = Some(raw_node);
+        if remaining == 0 && previous_last.is_some() {
+            self.tail = previous_last;
+        }
     }
 
-    fn reverse(&mut self) {
-        let mut prev = None;
-        let mut current = self.head.take();
+    /// Merges the sorted list `other` into this sorted list, leaving `other` empty. Of equal
+    /// values, those from `self` come first. This operation runs in O(n + m) time.
+    fn merge(&mut self, other: &mut LinkedList<T>)
+    where
+        T: Ord,
+    {
+        // SAFETY: both chains are owned by lists we hold mutably; `other` gives up its nodes.
+        let (head, tail) = unsafe { merge_chains(self.head, other.head.take()) };
+        other.tail = None;
+        self.head = head;
+        self.tail = tail;
+        self.length += std::mem::take(&mut other.length);
+    }
 
-        while let Some(mut current_node) = current {
-            let next = current_node.next.take();
-            current_node.next = prev;
-            prev = Some(current_node);
-            current = next;
+    /// Sorts the list stably by relinking nodes, with bottom-up merge sort: merge runs of 1,
+    /// then 2, then 4 and so on, until one run is left.
+    /// This operation runs in O(n log n) time and O(1) extra space, with no recursion.
+    fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        let mut width = 1;
+        while width < self.length {
+            let (mut head, mut tail): (Link<T>, Link<T>) = (None, None);
+            let mut rest = self.head;
+            while let Some(left) = rest {
+                // SAFETY: `left` starts the unsorted remainder of our nodes. Each pair of runs
+                // is cut off, merged and appended to the sorted chain before the next pair.
+                unsafe {
+                    let right = cut(left, width);
+                    rest = right.and_then(|right| cut(right, width));
+                    let (merged_head, merged_tail) = merge_chains(Some(left), right);
+                    match tail {
+                        Some(tail) => set_next(tail, merged_head),
+                        None => head = merged_head,
+                    }
+                    tail = merged_tail;
+                }
+            }
+            self.head = head;
+            self.tail = tail;
+            width *= 2;
         }
+    }
 
-        self.head = prev;
+    /// Moves all values less than `pivot` before the others, keeping the order within both
+    /// groups. This operation runs in O(n) time.
+    fn partition(&mut self, pivot: &T)
+    where
+        T: Ord,
+    {
+        let (mut less_head, mut less_tail): (Link<T>, Link<T>) = (None, None);
+        let (mut rest_head, mut rest_tail): (Link<T>, Link<T>) = (None, None);
+        let mut current = self.head;
 
-        self.tail = self
-            .head
-            .as_deref_mut()
-            .map(|node| {
-                let mut last = node;
-                while let Some(ref mut next_node) = last.next {
-                    last = next_node;
+        while let Some(node) = current {
+            // SAFETY: each live node is moved onto the end of one of the two chains, and the
+            // chains are joined back into the list below.
+            unsafe {
+                current = next(node);
+                set_next(node, None);
+                let (group_head, group_tail) = if value(node) < pivot {
+                    (&mut less_head, &mut less_tail)
+                } else {
+                    (&mut rest_head, &mut rest_tail)
+                };
+                match *group_tail {
+                    Some(tail) => set_next(tail, Some(node)),
+                    None => *group_head = Some(node),
                 }
-                last
-            })
-            .map(|last| last as *mut _);
+                *group_tail = Some(node);
+            }
+        }
+
+        match less_tail {
+            Some(less_tail) => {
+                // SAFETY: `less_tail` is a live node, the last of the smaller values.
+                unsafe { set_next(less_tail, rest_head) };
+                self.head = less_head;
+                self.tail = rest_tail.or(Some(less_tail));
+            }
+            None => {
+                self.head = rest_head;
+                self.tail = rest_tail;
+            }
+        }
+    }
+
+    /// Rotates the list `k` places to the right: the last `k` values move to the front.
+    /// This operation runs in O(n) time.
+    fn rotate(&mut self, k: usize) {
+        if self.length == 0 || k.is_multiple_of(self.length) {
+            return;
+        }
+        let new_tail = self.node_at(self.length - k % self.length - 1);
+        // SAFETY: `new_tail` and the old tail are live nodes; the list is closed into a ring
+        // and cut open again after `new_tail`.
+        unsafe {
+            let new_head = next(new_tail);
+            set_next(self.tail.expect("a non-empty list has a tail"), self.head);
+            set_next(new_tail, None);
+            self.head = new_head;
+        }
+        self.tail = Some(new_tail);
+    }
+
+    /// Removes and returns the `n`-th value from the end, where 1 is the last value, in a
+    /// single pass: a leading pointer starts `n` nodes ahead, so when it reaches the last node
+    /// the trailing one is just before the target.
+    ///
+    /// # Returns
+    ///
+    /// `None` if `n` is zero or greater than the length.
+    fn remove_nth_from_end(&mut self, n: usize) -> Option<T> {
+        if n == 0 {
+            return None;
+        }
+        // SAFETY: the pointers only walk live nodes of this list, and the target is unlinked
+        // before it is freed.
+        unsafe {
+            let mut lead = self.head?;
+            for _ in 1..n {
+                lead = next(lead)?;
+            }
+            // `lead` is the n-th node. If it is the last, the target is the head.
+            let Some(mut lead_next) = next(lead) else {
+                let head = self.head?;
+                let node = Box::from_raw(head.as_ptr());
+                self.head = node.next;
+                if self.head.is_none() {
+                    self.tail = None;
+                }
+                self.length -= 1;
+                return Some(node.value);
+            };
+            let mut trail = self.head?;
+            while let Some(after) = next(lead_next) {
+                lead_next = after;
+                trail = next(trail)?;
+            }
+            let target = next(trail)?;
+            set_next(trail, next(target));
+            if self.tail == Some(target) {
+                self.tail = Some(trail);
+            }
+            self.length -= 1;
+            Some(Box::from_raw(target.as_ptr()).value)
+        }
+    }
+
+    /// Returns the middle value, found with a slow pointer moving one node for every two the
+    /// fast one moves. Of two middle values, returns the second.
+    fn middle(&self) -> Option<&T> {
+        let mut slow = self.head?;
+        let mut fast = self.head;
+        // SAFETY: both pointers only walk live nodes, and the result borrows the list.
+        unsafe {
+            while let Some(after) = fast.and_then(|fast| next(fast)) {
+                slow = next(slow).expect("slow trails fast");
+                fast = next(after);
+            }
+            Some(value(slow))
+        }
+    }
+
+    /// Returns `true` if the values read the same backwards as forwards.
+    /// Runs in O(n) time and O(1) space by reversing the second half in place, comparing the
+    /// halves, then reversing it back, so the list is unchanged afterwards.
+    fn is_palindrome(&mut self) -> bool
+    where
+        T: PartialEq,
+    {
+        if self.length < 2 {
+            return true;
+        }
+        let half = self.length / 2;
+        let first_half_end = self.node_at((self.length - 1) / 2);
+        // SAFETY: the second half is reversed and restored between live nodes we own, and
+        // every pointer followed stays within the list.
+        unsafe {
+            let second_start = next(first_half_end).expect("at least two values");
+            let (reversed, last, _) = reverse_run(second_start, half);
+            set_next(last, None);
+
+            let mut left = self.head;
+            let mut right = Some(reversed);
+            let mut matches = true;
+            for _ in 0..half {
+                let (l, r) = (left.expect("first half"), right.expect("second half"));
+                if value(l) != value(r) {
+                    matches = false;
+                    break;
+                }
+                left = next(l);
+                right = next(r);
+            }
+
+            let (restored, _, _) = reverse_run(reversed, half);
+            set_next(first_half_end, Some(restored));
+            matches
+        }
     }
 }
 
 fn main() {
     let mut list = LinkedList::new();
-    list.push(1);
-    list.push(2);
-    list.push(3);
+    list.push_back(1);
+    list.push_back(2);
+    list.push_back(3);
 
     println!("Original list:");
-    let mut current = &list.head;
-    while let Some(ref node) = current {
-        println!("{}", node.value);
-        current = &node.next;
+    for value in list.to_vec() {
+        println!("{}", value);
     }
 
     list.reverse();
 
     println!("Reversed list:");
-    let mut current = &list.head;
-    while let Some(ref node) = current {
-        println!("{}", node.value);
-        current = &node.next;
+    for value in list.to_vec() {
+        println!("{}", value);
     }
+
+    // The tail now points at the old head, so pushing still appends at the end.
+    list.push_back(0);
+    println!("After pushing 0: {:?}", list);
+    list.reverse_recursive();
+    list.push_back(4);
+    println!("Reversed recursively, then pushed 4: {:?}", list);
+
+    let mut numbers: LinkedList<i32> = (1..=10).collect();
+    numbers.reverse_between(2, 5);
+    println!("Positions 2 to 5 reversed: {:?}", numbers);
+    numbers.reverse_between(6, 9);
+    numbers.push_back(11);
+    println!("Positions 6 to 9 reversed, 11 pushed: {:?}", numbers);
+
+    let mut groups: LinkedList<i32> = (1..=8).collect();
+    groups.reverse_k_group(3);
+    println!("Groups of 3 reversed: {:?}", groups);
+    groups.reverse_k_group(4);
+    groups.push_back(9);
+    println!("Then groups of 4, 9 pushed: {:?}", groups);
+
+    let mut odds: LinkedList<i32> = [1, 3, 5, 7].into_iter().collect();
+    let mut evens: LinkedList<i32> = [2, 4, 6, 8, 10, 12].into_iter().collect();
+    odds.merge(&mut evens);
+    odds.push_back(13);
+    println!(
+        "Merged: {:?} ({} values), other empty: {:?}",
+        odds,
+        odds.len(),
+        evens
+    );
+
+    let mut unsorted: LinkedList<i32> = [5, -2, 9, 0, 3, 3, 8, -7, 1].into_iter().collect();
+    unsorted.sort();
+    unsorted.push_back(100);
+    println!("Sorted, then 100 pushed: {:?}", unsorted);
+
+    let mut mixed: LinkedList<i32> = [1, 4, 3, 2, 5, 2].into_iter().collect();
+    mixed.partition(&3);
+    mixed.push_back(0);
+    println!("Partitioned around 3, then 0 pushed: {:?}", mixed);
+
+    let mut ring: LinkedList<i32> = (1..=5).collect();
+    ring.rotate(2);
+    println!("Rotated right by 2: {:?}", ring);
+    ring.rotate(12);
+    ring.push_back(6);
+    println!("Rotated by 12, 6 pushed: {:?}", ring);
+
+    println!(
+        "Removed 2nd from end: {:?}, then 5th from end: {:?}, removed 9th: {:?}",
+        ring.remove_nth_from_end(2),
+        ring.remove_nth_from_end(5),
+        ring.remove_nth_from_end(9)
+    );
+    println!("Left {:?}, middle {:?}", ring, ring.middle());
+    println!("Last removed: {:?}", ring.remove_nth_from_end(1));
+    ring.push_back(7);
+    println!("7 pushed: {:?}", ring);
+
+    let mut word: LinkedList<char> = "racecar".chars().collect();
+    let mut pair: LinkedList<char> = "abba".chars().collect();
+    let mut other: LinkedList<char> = "abca".chars().collect();
+    println!(
+        "Palindromes: racecar {}, abba {}, abca {}; abca unchanged: {:?}",
+        word.is_palindrome(),
+        pair.is_palindrome(),
+        other.is_palindrome(),
+        other
+    );
+    word.push_back('!');
+    println!("Middle of {:?}: {:?}", word, word.middle());
 }
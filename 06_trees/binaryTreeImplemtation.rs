@@ -1,9 +1,10 @@
+use std::borrow::Borrow;
 use std::cmp::Ordering;
 
 /// A Node struct that stores a value and links to left and right children in a binary search tree.
 #[derive(Debug)]
-struct Node<T> {
-    value: T,
+pub struct Node<T> {
+    pub value: T,
     left: Option<Box<Node<T>>>,
     right: Option<Box<Node<T>>>,
 }
@@ -19,15 +20,33 @@ impl<T: Ord> Node<T> {
 }
 
 /// A BinarySearchTree struct that uses a linked list of Nodes to store elements in a binary search tree.
+///
+/// The tree is not balanced, so values inserted in sorted order make it a list. `in_order`
+/// and dropping the tree walk it with an explicit stack, so even then they cannot overflow
+/// the call stack.
 #[derive(Debug)]
-struct BinarySearchTree<T> {
+pub struct BinarySearchTree<T> {
     root: Option<Box<Node<T>>>,
+    length: usize,
 }
 
 impl<T: Ord> BinarySearchTree<T> {
     /// Constructs a new, empty BinarySearchTree.
     pub fn new() -> Self {
-        BinarySearchTree { root: None }
+        BinarySearchTree {
+            root: None,
+            length: 0,
+        }
+    }
+
+    /// Returns the number of values in the tree.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the tree holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
     }
 
     /// Inserts a new value into the binary search tree.
@@ -41,6 +60,23 @@ impl<T: Ord> BinarySearchTree<T> {
             }
         }
         *current = Some(Box::new(Node::new(value)));
+        self.length += 1;
+    }
+
+    /// Inserts a value, replacing and returning an equal value that is already in the tree.
+    /// Useful when values are ordered by part of their contents, like a key.
+    pub fn replace(&mut self, value: T) -> Option<T> {
+        let mut current = &mut self.root;
+        while let Some(ref mut found) = current {
+            match value.cmp(&found.value) {
+                Ordering::Less => current = &mut found.left,
+                Ordering::Greater => current = &mut found.right,
+                Ordering::Equal => return Some(std::mem::replace(&mut found.value, value)),
+            }
+        }
+        *current = Some(Box::new(Node::new(value)));
+        self.length += 1;
+        None
     }
 
     /// Finds a value in the binary search tree and returns a reference to it, if it exists.
@@ -56,8 +92,45 @@ impl<T: Ord> BinarySearchTree<T> {
         None
     }
 
+    /// Finds the value equal to `value`, which may be any borrowed form of it.
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut current = &self.root;
+        while let Some(ref found) = current {
+            match value.cmp(found.value.borrow()) {
+                Ordering::Less => current = &found.left,
+                Ordering::Greater => current = &found.right,
+                Ordering::Equal => return Some(&found.value),
+            }
+        }
+        None
+    }
+
+    /// Returns every value in ascending order.
+    pub fn in_order(&self) -> Vec<&T> {
+        let mut values = Vec::with_capacity(self.length);
+        let mut stack = Vec::new();
+        let mut current = self.root.as_deref();
+        while current.is_some() || !stack.is_empty() {
+            while let Some(node) = current {
+                stack.push(node);
+                current = node.left.as_deref();
+            }
+            let node = stack.pop().unwrap();
+            values.push(&node.value);
+            current = node.right.as_deref();
+        }
+        values
+    }
+
     /// Deletes a value from the binary search tree, if it exists.
     pub fn remove(&mut self, value: T) {
+        if self.get(&value).is_some() {
+            self.length -= 1;
+        }
         self.root = BinarySearchTree::remove_node(self.root.take(), value);
     }
 
@@ -99,6 +172,24 @@ impl<T: Ord> BinarySearchTree<T> {
     }
 }
 
+impl<T: Ord> Default for BinarySearchTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for BinarySearchTree<T> {
+    /// Frees the nodes one at a time, so a degenerate tree cannot overflow the call stack the
+    /// way the default recursive drop of the boxes would.
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
 fn main() {
     let mut tree = BinarySearchTree::new();
     tree.insert(9);
@@ -115,4 +206,5 @@ fn main() {
     } else {
         println!("Node not found.");
     }
+    println!("{} values in order: {:?}", tree.len(), tree.in_order());
 }
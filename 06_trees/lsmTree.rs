@@ -0,0 +1,567 @@
+/*
+  A log-structured merge tree (LSM tree): a key-value store that turns every write into a
+  sequential one.
+
+  Writes go to an in-memory memtable, here the binary search tree from binaryTreeImplemtation.rs
+  holding entries ordered by key. When the memtable is full, its entries are written out in key
+  order as an immutable sorted-string table (SSTable) and the memtable starts again empty.
+  SSTables are never changed after they are written, so a removal cannot delete anything; it
+  writes a tombstone instead, which hides older values of the key.
+
+  A point lookup checks the memtable first, then the SSTables from newest to oldest, and stops
+  at the first table that knows the key. Each SSTable keeps a sparse index, the key and file
+  offset of every 16th entry, so a lookup reads at most one short run of entries from disk. An
+  optional Bloom filter per table, the one from 02_hashTables/probabilisticFilters.rs, answers
+  "definitely not here" for most missing keys without reading the table at all.
+
+  As tables pile up, lookups slow down and overwritten values waste space. Compaction merges
+  all tables into one with the k-way merge from 01_arrays/mergeSortedArrays.rs, keeping only
+  the newest version of each key and dropping tombstones, since no older table is left for them
+  to hide anything in.
+
+  SSTable layout, all integers little-endian:
+
+      entries:  [key length: u32][key][tag: u8, 1 = value, 0 = tombstone][value length: u32][value]
+      index:    [count: u32] then [key length: u32][key][entry offset: u64] per indexed entry
+      bloom:    the filter as written by `BloomFilter::to_bytes`
+      footer:   [index offset: u64][bloom offset: u64, 0 if none][entry count: u64]["SST1"]
+
+  The memtable is only in memory, so writes since the last flush are lost if the process dies;
+  a write-ahead log like the one in 02_hashTables/kvStore.rs would close that gap.
+*/
+
+use std::borrow::Borrow;
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Error, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+#[allow(dead_code)]
+#[path = "binaryTreeImplemtation.rs"]
+mod binary_tree;
+
+#[allow(dead_code)]
+#[path = "../01_arrays/mergeSortedArrays.rs"]
+mod merge_sorted_arrays;
+
+#[allow(dead_code)]
+#[path = "../02_hashTables/probabilisticFilters.rs"]
+mod probabilistic_filters;
+
+use binary_tree::BinarySearchTree;
+use merge_sorted_arrays::merge_k_sorted_arrays;
+use probabilistic_filters::BloomFilter;
+
+/// A key and its value, or `None` for a tombstone.
+///
+/// Entries compare by key alone, so the memtable can find one by its key and the merge of
+/// several tables keeps entries with the same key in the order of their tables.
+#[derive(Clone)]
+struct Entry {
+    key: Vec<u8>,
+    value: Option<Vec<u8>>,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+impl Borrow<[u8]> for Entry {
+    fn borrow(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+/// The identifier at the end of every SSTable file.
+const SSTABLE_MAGIC: &[u8; 4] = b"SST1";
+
+/// The size of the SSTable footer.
+const FOOTER_SIZE: u64 = 8 + 8 + 8 + 4;
+
+/// Every this many entries, one goes into the sparse index.
+const INDEX_INTERVAL: usize = 16;
+
+/// The default number of memtable entries that triggers a flush.
+const DEFAULT_MEMTABLE_LIMIT: usize = 1024;
+
+/// The number of SSTables that triggers a compaction.
+const COMPACTION_TRIGGER: usize = 4;
+
+fn write_u32<W: Write>(writer: &mut W, value: u32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_u64<W: Write>(writer: &mut W, value: u64) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    write_u32(writer, bytes.len() as u32)?;
+    writer.write_all(bytes)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![0; read_u32(reader)? as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Reads one entry, returning it with its size in bytes.
+fn read_entry<R: Read>(reader: &mut R) -> io::Result<(Entry, u64)> {
+    let key = read_bytes(reader)?;
+    let mut tag = [0; 1];
+    reader.read_exact(&mut tag)?;
+    let value = read_bytes(reader)?;
+    let size = (4 + key.len() + 1 + 4 + value.len()) as u64;
+    let value = (tag[0] == 1).then_some(value);
+    Ok((Entry { key, value }, size))
+}
+
+/// An immutable sorted-string table on disk. Only its sparse index and Bloom filter are kept
+/// in memory.
+struct SsTable {
+    path: PathBuf,
+    /// Every `INDEX_INTERVAL`-th key, with the file offset of its entry.
+    index: Vec<(Vec<u8>, u64)>,
+    bloom: Option<BloomFilter>,
+    /// The offset where the entries end and the index begins.
+    data_end: u64,
+    entry_count: u64,
+}
+
+impl SsTable {
+    /// Writes `entries`, which must be sorted by key without duplicates, to a new table at
+    /// `path`. The file is written under a temporary name and renamed into place, so a table
+    /// on disk is always complete.
+    fn write(
+        path: PathBuf,
+        entries: &[(&[u8], Option<&[u8]>)],
+        bloom_false_positive_rate: Option<f64>,
+    ) -> io::Result<Self> {
+        let temporary = path.with_extension("tmp");
+        let mut writer = BufWriter::new(File::create(&temporary)?);
+        let mut index = Vec::new();
+        let mut bloom =
+            bloom_false_positive_rate.map(|rate| BloomFilter::new(entries.len().max(1), rate));
+        let mut offset = 0u64;
+
+        for (position, &(key, value)) in entries.iter().enumerate() {
+            if position % INDEX_INTERVAL == 0 {
+                index.push((key.to_vec(), offset));
+            }
+            if let Some(bloom) = &mut bloom {
+                bloom.insert(key);
+            }
+            write_bytes(&mut writer, key)?;
+            writer.write_all(&[value.is_some() as u8])?;
+            write_bytes(&mut writer, value.unwrap_or_default())?;
+            offset += (4 + key.len() + 1 + 4 + value.map_or(0, <[u8]>::len)) as u64;
+        }
+
+        let data_end = offset;
+        write_u32(&mut writer, index.len() as u32)?;
+        offset += 4;
+        for (key, entry_offset) in &index {
+            write_bytes(&mut writer, key)?;
+            write_u64(&mut writer, *entry_offset)?;
+            offset += (4 + key.len() + 8) as u64;
+        }
+
+        let bloom_offset = match &bloom {
+            Some(bloom) => {
+                writer.write_all(&bloom.to_bytes())?;
+                offset
+            }
+            None => 0,
+        };
+
+        write_u64(&mut writer, data_end)?;
+        write_u64(&mut writer, bloom_offset)?;
+        write_u64(&mut writer, entries.len() as u64)?;
+        writer.write_all(SSTABLE_MAGIC)?;
+        writer.into_inner()?.sync_all()?;
+        fs::rename(&temporary, &path)?;
+
+        Ok(SsTable {
+            path,
+            index,
+            bloom,
+            data_end,
+            entry_count: entries.len() as u64,
+        })
+    }
+
+    /// Opens an existing table, reading its footer, sparse index and Bloom filter.
+    fn open(path: PathBuf) -> io::Result<Self> {
+        let invalid = || {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("corrupt SSTable {}", path.display()),
+            )
+        };
+        let mut reader = BufReader::new(File::open(&path)?);
+        let file_size = reader.seek(SeekFrom::End(0))?;
+        if file_size < FOOTER_SIZE {
+            return Err(invalid());
+        }
+        reader.seek(SeekFrom::End(-(FOOTER_SIZE as i64)))?;
+        let data_end = read_u64(&mut reader)?;
+        let bloom_offset = read_u64(&mut reader)?;
+        let entry_count = read_u64(&mut reader)?;
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        let footer_start = file_size - FOOTER_SIZE;
+        if &magic != SSTABLE_MAGIC || data_end > footer_start || bloom_offset > footer_start {
+            return Err(invalid());
+        }
+
+        reader.seek(SeekFrom::Start(data_end))?;
+        let mut index = Vec::new();
+        for _ in 0..read_u32(&mut reader)? {
+            let key = read_bytes(&mut reader)?;
+            index.push((key, read_u64(&mut reader)?));
+        }
+
+        let bloom = if bloom_offset == 0 {
+            None
+        } else {
+            reader.seek(SeekFrom::Start(bloom_offset))?;
+            let mut bytes = vec![0; (footer_start - bloom_offset) as usize];
+            reader.read_exact(&mut bytes)?;
+            Some(BloomFilter::from_bytes(&bytes)?)
+        };
+
+        Ok(SsTable {
+            path,
+            index,
+            bloom,
+            data_end,
+            entry_count,
+        })
+    }
+
+    /// Returns `true` if the Bloom filter rules `key` out, so the table need not be read.
+    fn skips(&self, key: &[u8]) -> bool {
+        self.bloom
+            .as_ref()
+            .is_some_and(|bloom| !bloom.contains(key))
+    }
+
+    /// Looks `key` up on disk. The sparse index narrows the search to one run of at most
+    /// `INDEX_INTERVAL` entries, which is read sequentially.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the table does not mention the key, `Some(None)` for a tombstone, or
+    /// `Some(Some(value))`.
+    fn get(&self, key: &[u8]) -> io::Result<Option<Option<Vec<u8>>>> {
+        let run = self
+            .index
+            .partition_point(|(indexed, _)| indexed.as_slice() <= key);
+        if run == 0 {
+            return Ok(None);
+        }
+        let mut offset = self.index[run - 1].1;
+        let end = self
+            .index
+            .get(run)
+            .map_or(self.data_end, |(_, offset)| *offset);
+
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        reader.seek(SeekFrom::Start(offset))?;
+        while offset < end {
+            let (entry, size) = read_entry(&mut reader)?;
+            match entry.key.as_slice().cmp(key) {
+                Ordering::Less => offset += size,
+                Ordering::Equal => return Ok(Some(entry.value)),
+                Ordering::Greater => break,
+            }
+        }
+        Ok(None)
+    }
+
+    /// Reads every entry of the table, in key order.
+    fn entries(&self) -> io::Result<Vec<Entry>> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut entries = Vec::with_capacity(self.entry_count as usize);
+        for _ in 0..self.entry_count {
+            entries.push(read_entry(&mut reader)?.0);
+        }
+        Ok(entries)
+    }
+}
+
+/// Merges sorted runs of entries with the k-way merge from 01_arrays/mergeSortedArrays.rs.
+///
+/// `runs` must be ordered newest first. Entries with the same key come out in the order of
+/// their runs, so the newest one is kept and the older ones are dropped.
+/// This runs in O(n log k) time for n entries in k runs.
+fn merge_runs(runs: &[Vec<Entry>]) -> Vec<Entry> {
+    let runs: Vec<&[Entry]> = runs.iter().map(Vec::as_slice).collect();
+    let mut merged = merge_k_sorted_arrays(&runs);
+    merged.dedup();
+    merged
+}
+
+/// A log-structured merge tree storing byte-string keys and values in one directory.
+struct LsmTree {
+    directory: PathBuf,
+    /// Unflushed writes; `None` values are tombstones.
+    memtable: BinarySearchTree<Entry>,
+    memtable_limit: usize,
+    /// SSTables, oldest first, with the file numbers they are named after.
+    tables: Vec<(u64, SsTable)>,
+    next_table: u64,
+    bloom_false_positive_rate: Option<f64>,
+    /// The number of SSTables read from disk by lookups, for measuring the Bloom filters.
+    table_reads: Cell<usize>,
+}
+
+impl LsmTree {
+    /// Opens the tree stored in `directory`, creating the directory if needed.
+    /// Flushes after 1024 writes and builds Bloom filters with a 1% false-positive rate.
+    pub fn open<P: AsRef<Path>>(directory: P) -> io::Result<Self> {
+        Self::with_options(directory, DEFAULT_MEMTABLE_LIMIT, Some(0.01))
+    }
+
+    /// Opens the tree stored in `directory` with custom settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - Where the SSTables are kept.
+    /// * `memtable_limit` - The number of memtable entries that triggers a flush. Must be positive.
+    /// * `bloom_false_positive_rate` - The Bloom filter error rate for new tables, between 0
+    ///   and 1 exclusive, or `None` for no filters.
+    pub fn with_options<P: AsRef<Path>>(
+        directory: P,
+        memtable_limit: usize,
+        bloom_false_positive_rate: Option<f64>,
+    ) -> io::Result<Self> {
+        assert!(
+            memtable_limit > 0,
+            "the memtable must hold at least one entry"
+        );
+        let directory = directory.as_ref().to_path_buf();
+        fs::create_dir_all(&directory)?;
+
+        let mut tables = Vec::new();
+        for file in fs::read_dir(&directory)? {
+            let path = file?.path();
+            let number = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok());
+            match (number, path.extension().and_then(|ext| ext.to_str())) {
+                (Some(number), Some("sst")) => tables.push((number, SsTable::open(path)?)),
+                // A leftover from a crash while a table was being written.
+                (_, Some("tmp")) => fs::remove_file(path)?,
+                _ => {}
+            }
+        }
+        tables.sort_by_key(|(number, _)| *number);
+        let next_table = tables.last().map_or(0, |(number, _)| number + 1);
+
+        Ok(LsmTree {
+            directory,
+            memtable: BinarySearchTree::new(),
+            memtable_limit,
+            tables,
+            next_table,
+            bloom_false_positive_rate,
+            table_reads: Cell::new(0),
+        })
+    }
+
+    /// Returns the number of SSTables on disk.
+    pub fn table_count(&self) -> usize {
+        self.tables.len()
+    }
+
+    /// Returns the number of SSTables lookups have read so far.
+    pub fn table_reads(&self) -> usize {
+        self.table_reads.get()
+    }
+
+    /// Records a write in the memtable, flushing it if it is full.
+    fn write(&mut self, key: &[u8], value: Option<&[u8]>) -> io::Result<()> {
+        self.memtable.replace(Entry {
+            key: key.to_vec(),
+            value: value.map(<[u8]>::to_vec),
+        });
+        if self.memtable.len() >= self.memtable_limit {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Stores `value` under `key`.
+    /// This operation runs in O(log m) expected time for m memtable entries, plus an
+    /// occasional flush or compaction.
+    pub fn set(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        self.write(key, Some(value))
+    }
+
+    /// Removes `key` by writing a tombstone.
+    pub fn remove(&mut self, key: &[u8]) -> io::Result<()> {
+        self.write(key, None)
+    }
+
+    /// Retrieves the value stored under `key`: the memtable first, then each SSTable from
+    /// newest to oldest, skipping tables whose Bloom filter rules the key out.
+    pub fn get(&self, key: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        if let Some(entry) = self.memtable.get(key) {
+            return Ok(entry.value.clone());
+        }
+        for (_, table) in self.tables.iter().rev() {
+            if table.skips(key) {
+                continue;
+            }
+            self.table_reads.set(self.table_reads.get() + 1);
+            if let Some(value) = table.get(key)? {
+                return Ok(value);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Writes the memtable to a new SSTable and empties it, then compacts if there are now
+    /// too many tables.
+    pub fn flush(&mut self) -> io::Result<()> {
+        if self.memtable.is_empty() {
+            return Ok(());
+        }
+        let memtable = std::mem::take(&mut self.memtable);
+        let entries: Vec<(&[u8], Option<&[u8]>)> = memtable
+            .in_order()
+            .into_iter()
+            .map(|entry| (entry.key.as_slice(), entry.value.as_deref()))
+            .collect();
+        self.add_table(&entries)?;
+
+        if self.tables.len() >= COMPACTION_TRIGGER {
+            self.compact()?;
+        }
+        Ok(())
+    }
+
+    fn add_table(&mut self, entries: &[(&[u8], Option<&[u8]>)]) -> io::Result<()> {
+        let path = self.directory.join(format!("{:06}.sst", self.next_table));
+        let table = SsTable::write(path, entries, self.bloom_false_positive_rate)?;
+        self.tables.push((self.next_table, table));
+        self.next_table += 1;
+        Ok(())
+    }
+
+    /// Merges every SSTable into one, keeping only the newest version of each key and
+    /// dropping tombstones.
+    ///
+    /// The merged table is written before the old ones are deleted, and gets a higher number
+    /// than all of them, so a crash part-way through leaves tables that still read correctly.
+    pub fn compact(&mut self) -> io::Result<()> {
+        if self.tables.len() < 2 {
+            return Ok(());
+        }
+        let runs = self
+            .tables
+            .iter()
+            .rev()
+            .map(|(_, table)| table.entries())
+            .collect::<io::Result<Vec<_>>>()?;
+        let merged = merge_runs(&runs);
+        let live: Vec<(&[u8], Option<&[u8]>)> = merged
+            .iter()
+            .filter_map(|entry| Some((entry.key.as_slice(), Some(entry.value.as_deref()?))))
+            .collect();
+
+        // Only forget the old tables once the merged one is safely written.
+        let old_count = self.tables.len();
+        self.add_table(&live)?;
+        let old_tables: Vec<_> = self.tables.drain(..old_count).collect();
+        for (_, table) in old_tables {
+            fs::remove_file(table.path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Shows writes, lookups, Bloom filters and compaction, in a scratch directory.
+fn demo() -> io::Result<()> {
+    let directory = env::temp_dir().join(format!("lsmTree-demo-{}", std::process::id()));
+    let mut tree = LsmTree::with_options(&directory, 100, Some(0.01))?;
+
+    for i in 0..350 {
+        tree.set(
+            format!("user-{:04}", i).as_bytes(),
+            format!("score {}", i).as_bytes(),
+        )?;
+    }
+    tree.set(b"user-0007", b"score 700")?;
+    tree.remove(b"user-0042")?;
+    println!("{} SSTables after 352 writes", tree.table_count());
+
+    let show =
+        |value: Option<Vec<u8>>| value.map(|bytes| String::from_utf8_lossy(&bytes).into_owned());
+    println!("user-0007: {:?}", show(tree.get(b"user-0007")?));
+    println!("user-0042: {:?}", show(tree.get(b"user-0042")?));
+    println!("user-0123: {:?}", show(tree.get(b"user-0123")?));
+
+    // Missing keys: the Bloom filters let almost every lookup skip every table.
+    for i in 0..1000 {
+        tree.get(format!("nobody-{}", i).as_bytes())?;
+    }
+    println!(
+        "SSTable reads for 1000 missing keys: {}",
+        tree.table_reads()
+    );
+
+    tree.flush()?;
+    tree.compact()?;
+    drop(tree);
+
+    let tree = LsmTree::open(&directory)?;
+    println!(
+        "After compaction and reopening: {} SSTable, user-0007: {:?}, user-0042: {:?}",
+        tree.table_count(),
+        show(tree.get(b"user-0007")?),
+        show(tree.get(b"user-0042")?)
+    );
+
+    fs::remove_dir_all(&directory)
+}
+
+fn main() {
+    if let Err(err) = demo() {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
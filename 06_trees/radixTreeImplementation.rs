@@ -0,0 +1,377 @@
+use std::cmp::Reverse;
+use std::collections::btree_map::{self, Entry};
+use std::collections::{BTreeMap, BinaryHeap};
+
+/// A node of the radix tree. The edge from its parent is labelled with a whole byte string.
+#[derive(Debug)]
+struct RadixNode<V> {
+    label: Vec<u8>,
+    value: Option<V>,
+    /// Children keyed by the first byte of their label. No two children share a first byte.
+    children: BTreeMap<u8, RadixNode<V>>,
+}
+
+impl<V> RadixNode<V> {
+    fn new(label: Vec<u8>, value: Option<V>) -> Self {
+        RadixNode {
+            label,
+            value,
+            children: BTreeMap::new(),
+        }
+    }
+}
+
+/// A path-compressed trie (radix tree) that maps byte-string keys to values.
+///
+/// A plain Trie spends one node per byte, even along stretches where there is nothing to
+/// choose between. A radix tree collapses every chain of single-child nodes into one edge
+/// labelled with the whole stretch, so "romane" and "romanus" need three nodes
+/// ("roman", "e", "us") instead of eight.
+#[derive(Debug)]
+struct RadixTree<V> {
+    root: RadixNode<V>,
+    length: usize,
+}
+
+/// Returns the length of the longest common prefix of `a` and `b`.
+fn common_prefix_length(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+/// An iterator over the entries below a node, in lexicographic key order.
+struct Iter<'a, V> {
+    /// Each level holds the children still to visit and the length of the label that
+    /// led to them, so the key can be shortened again when the level is exhausted.
+    stack: Vec<(btree_map::Iter<'a, u8, RadixNode<V>>, usize)>,
+    key: Vec<u8>,
+    pending: Option<&'a V>,
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (Vec<u8>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(value) = self.pending.take() {
+            return Some((self.key.clone(), value));
+        }
+
+        loop {
+            let (children, _) = self.stack.last_mut()?;
+            match children.next() {
+                Some((_, child)) => {
+                    self.key.extend_from_slice(&child.label);
+                    self.stack.push((child.children.iter(), child.label.len()));
+                    if let Some(value) = &child.value {
+                        return Some((self.key.clone(), value));
+                    }
+                }
+                None => {
+                    let (_, label_length) = self.stack.pop().unwrap();
+                    self.key
+                        .truncate(self.key.len().saturating_sub(label_length));
+                }
+            }
+        }
+    }
+}
+
+impl<V> RadixTree<V> {
+    /// Constructs a new, empty RadixTree.
+    pub fn new() -> Self {
+        RadixTree {
+            root: RadixNode::new(Vec::new(), None),
+            length: 0,
+        }
+    }
+
+    /// Returns the number of keys stored in the tree.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the tree holds no keys.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns the number of nodes in the tree, including the root.
+    pub fn node_count(&self) -> usize {
+        fn count<V>(node: &RadixNode<V>) -> usize {
+            1 + node.children.values().map(count).sum::<usize>()
+        }
+        count(&self.root)
+    }
+
+    /// Inserts a key-value pair into the tree.
+    /// This operation runs in O(k) time, where k is the length of the key.
+    ///
+    /// When the key diverges from an edge part-way along its label, the edge is split in
+    /// two at the point of divergence.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to insert.
+    /// * `value` - The value associated with the key.
+    ///
+    /// # Returns
+    ///
+    /// The value previously stored under `key`, if there was one.
+    pub fn set<K: AsRef<[u8]>>(&mut self, key: K, value: V) -> Option<V> {
+        let mut current = &mut self.root;
+        let mut rest = key.as_ref();
+
+        while let Some(&first) = rest.first() {
+            let child = match current.children.entry(first) {
+                Entry::Vacant(entry) => {
+                    entry.insert(RadixNode::new(rest.to_vec(), Some(value)));
+                    self.length += 1;
+                    return None;
+                }
+                Entry::Occupied(entry) => entry.into_mut(),
+            };
+
+            let common = common_prefix_length(&child.label, rest);
+            if common < child.label.len() {
+                let suffix = child.label.split_off(common);
+                let mut lower = RadixNode::new(suffix, child.value.take());
+                lower.children = std::mem::take(&mut child.children);
+                child.children.insert(lower.label[0], lower);
+            }
+
+            current = child;
+            rest = &rest[common..];
+        }
+
+        let old = current.value.replace(value);
+        if old.is_none() {
+            self.length += 1;
+        }
+        old
+    }
+
+    /// Follows `key` from the root and returns the node it ends at, if the path exists.
+    fn find_node(&self, key: &[u8]) -> Option<&RadixNode<V>> {
+        let mut current = &self.root;
+        let mut rest = key;
+        while let Some(first) = rest.first() {
+            current = current.children.get(first)?;
+            rest = rest.strip_prefix(current.label.as_slice())?;
+        }
+        Some(current)
+    }
+
+    /// Retrieves a reference to the value stored under `key`.
+    /// This operation runs in O(k) time, where k is the length of the key.
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Option<&V> {
+        self.find_node(key.as_ref())?.value.as_ref()
+    }
+
+    /// Retrieves a mutable reference to the value stored under `key`.
+    pub fn get_mut<K: AsRef<[u8]>>(&mut self, key: K) -> Option<&mut V> {
+        let mut current = &mut self.root;
+        let mut rest = key.as_ref();
+        while let Some(first) = rest.first() {
+            current = current.children.get_mut(first)?;
+            rest = rest.strip_prefix(current.label.as_slice())?;
+        }
+        current.value.as_mut()
+    }
+
+    /// Returns `true` if the tree holds a value for `key`.
+    pub fn contains_key<K: AsRef<[u8]>>(&self, key: K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes `key` from the tree and returns its value.
+    ///
+    /// A node left without a value is deleted if it has no children, or merged with its
+    /// child if it has exactly one, so the tree stays fully path-compressed.
+    pub fn remove<K: AsRef<[u8]>>(&mut self, key: K) -> Option<V> {
+        let removed = Self::remove_from(&mut self.root, key.as_ref());
+        if removed.is_some() {
+            self.length -= 1;
+        }
+        removed
+    }
+
+    fn remove_from(node: &mut RadixNode<V>, key: &[u8]) -> Option<V> {
+        let Some(&first) = key.first() else {
+            return node.value.take();
+        };
+
+        let child = node.children.get_mut(&first)?;
+        let rest = key.strip_prefix(child.label.as_slice())?;
+        let removed = Self::remove_from(child, rest)?;
+
+        if child.value.is_none() {
+            if child.children.is_empty() {
+                node.children.remove(&first);
+            } else if child.children.len() == 1 {
+                let (_, only) = child.children.pop_first().unwrap();
+                child.label.extend_from_slice(&only.label);
+                child.value = only.value;
+                child.children = only.children;
+            }
+        }
+        Some(removed)
+    }
+
+    /// Returns an iterator over all entries in lexicographic key order.
+    pub fn iter(&self) -> Iter<'_, V> {
+        self.starts_with("")
+    }
+
+    /// Returns all keys stored in the tree, in lexicographic order.
+    pub fn keys(&self) -> Vec<Vec<u8>> {
+        self.iter().map(|(key, _)| key).collect()
+    }
+
+    /// Returns an iterator over the entries whose keys start with `prefix`.
+    ///
+    /// The prefix may end part-way along an edge; every key below that edge still matches.
+    pub fn starts_with<K: AsRef<[u8]>>(&self, prefix: K) -> Iter<'_, V> {
+        let mut current = &self.root;
+        let mut key = Vec::new();
+        let mut rest = prefix.as_ref();
+
+        while let Some(first) = rest.first() {
+            let child = match current.children.get(first) {
+                Some(child) if child.label.starts_with(rest) || rest.starts_with(&child.label) => {
+                    child
+                }
+                _ => {
+                    return Iter {
+                        stack: Vec::new(),
+                        key: Vec::new(),
+                        pending: None,
+                    }
+                }
+            };
+            key.extend_from_slice(&child.label);
+            rest = &rest[rest.len().min(child.label.len())..];
+            current = child;
+        }
+
+        Iter {
+            stack: vec![(current.children.iter(), 0)],
+            key,
+            pending: current.value.as_ref(),
+        }
+    }
+
+    /// Finds the longest key in the tree that is a prefix of `query`.
+    ///
+    /// # Returns
+    ///
+    /// The matching prefix of `query` and its value, or `None` if no key is a prefix.
+    pub fn longest_prefix_match<'q>(&self, query: &'q [u8]) -> Option<(&'q [u8], &V)> {
+        let mut current = &self.root;
+        let mut consumed = 0;
+        let mut best = current.value.as_ref().map(|value| (0, value));
+
+        while let Some(child) = query
+            .get(consumed)
+            .and_then(|first| current.children.get(first))
+        {
+            if !query[consumed..].starts_with(&child.label) {
+                break;
+            }
+            consumed += child.label.len();
+            current = child;
+            if let Some(value) = &current.value {
+                best = Some((consumed, value));
+            }
+        }
+
+        best.map(|(length, value)| (&query[..length], value))
+    }
+
+    /// Returns up to `k` entries starting with `prefix`, ranked by their stored weight.
+    ///
+    /// A min-heap of size `k` keeps the best candidates seen so far, so this runs in
+    /// O(m log k) time for m matching keys.
+    pub fn autocomplete<K: AsRef<[u8]>>(&self, prefix: K, k: usize) -> Vec<(Vec<u8>, &V)>
+    where
+        V: Ord,
+    {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        // Ties on weight go to the lexicographically smaller key.
+        let mut heap = BinaryHeap::new();
+        for (key, value) in self.starts_with(prefix) {
+            heap.push((Reverse(value), key));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|(Reverse(value), key)| (key, value))
+            .collect()
+    }
+}
+
+fn main() {
+    let mut tree = RadixTree::new();
+    for (word, weight) in [
+        ("romane", 3),
+        ("romanus", 5),
+        ("romulus", 9),
+        ("rubens", 4),
+        ("ruber", 2),
+        ("rubicon", 7),
+        ("rubicundus", 1),
+    ] {
+        tree.set(word, weight);
+    }
+    tree.set("ruber", 6); // Updates the weight in place.
+    *tree.get_mut("rubens").unwrap() += 1;
+
+    println!(
+        "Words stored: {} in {} nodes",
+        tree.len(),
+        tree.node_count()
+    );
+    println!("Weight of 'rubicon': {:?}", tree.get("rubicon"));
+    println!("Contains 'rub': {}", tree.contains_key(b"rub"));
+
+    let completions: Vec<String> = tree
+        .starts_with("rubi")
+        .map(|(key, _)| String::from_utf8_lossy(&key).into_owned())
+        .collect();
+    println!("Words starting with 'rubi': {:?}", completions);
+
+    for (key, weight) in tree.autocomplete("r", 3) {
+        println!(
+            "Top suggestion: {} ({})",
+            String::from_utf8_lossy(&key),
+            weight
+        );
+    }
+
+    println!("Removed 'romane': {:?}", tree.remove("romane"));
+    println!(
+        "All words: {:?} in {} nodes",
+        tree.keys()
+            .iter()
+            .map(|key| String::from_utf8_lossy(key))
+            .collect::<Vec<_>>(),
+        tree.node_count()
+    );
+
+    let mut routes = RadixTree::new();
+    routes.set("/", "index");
+    routes.set("/api", "api root");
+    routes.set("/api/users", "users");
+    if let Some((prefix, handler)) = routes.longest_prefix_match(b"/api/users/42") {
+        println!(
+            "Route for /api/users/42: {} -> {}",
+            String::from_utf8_lossy(prefix),
+            handler
+        );
+    }
+    println!("Routes empty: {}", routes.is_empty());
+}
@@ -0,0 +1,298 @@
+use std::cmp::Reverse;
+use std::collections::btree_map;
+use std::collections::{BTreeMap, BinaryHeap};
+
+/// A node of the trie. Each edge to a child is labelled with a single byte.
+#[derive(Debug)]
+struct TrieNode<V> {
+    value: Option<V>,
+    children: BTreeMap<u8, TrieNode<V>>,
+}
+
+impl<V> TrieNode<V> {
+    fn new() -> Self {
+        TrieNode {
+            value: None,
+            children: BTreeMap::new(),
+        }
+    }
+}
+
+/// A Trie (prefix tree) that maps byte-string keys to values.
+///
+/// Keys that share a prefix share the path from the root, which makes prefix queries cheap:
+/// every key starting with "car" lives under the node reached by following 'c', 'a', 'r'.
+/// Children are kept in a `BTreeMap`, so iteration yields keys in lexicographic order.
+#[derive(Debug)]
+struct Trie<V> {
+    root: TrieNode<V>,
+    length: usize,
+}
+
+/// An iterator over the entries below a node, in lexicographic key order.
+struct Iter<'a, V> {
+    stack: Vec<btree_map::Iter<'a, u8, TrieNode<V>>>,
+    key: Vec<u8>,
+    pending: Option<&'a V>,
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (Vec<u8>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(value) = self.pending.take() {
+            return Some((self.key.clone(), value));
+        }
+
+        loop {
+            match self.stack.last_mut()?.next() {
+                Some((&byte, child)) => {
+                    self.key.push(byte);
+                    self.stack.push(child.children.iter());
+                    if let Some(value) = &child.value {
+                        return Some((self.key.clone(), value));
+                    }
+                }
+                None => {
+                    self.stack.pop();
+                    self.key.pop();
+                }
+            }
+        }
+    }
+}
+
+impl<V> Trie<V> {
+    /// Constructs a new, empty Trie.
+    pub fn new() -> Self {
+        Trie {
+            root: TrieNode::new(),
+            length: 0,
+        }
+    }
+
+    /// Returns the number of keys stored in the trie.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the trie holds no keys.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Follows `key` from the root and returns the node it ends at, if the path exists.
+    fn find_node(&self, key: &[u8]) -> Option<&TrieNode<V>> {
+        let mut current = &self.root;
+        for byte in key {
+            current = current.children.get(byte)?;
+        }
+        Some(current)
+    }
+
+    /// Inserts a key-value pair into the trie.
+    /// This operation runs in O(k) time, where k is the length of the key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to insert.
+    /// * `value` - The value associated with the key.
+    ///
+    /// # Returns
+    ///
+    /// The value previously stored under `key`, if there was one.
+    pub fn set<K: AsRef<[u8]>>(&mut self, key: K, value: V) -> Option<V> {
+        let mut current = &mut self.root;
+        for &byte in key.as_ref() {
+            current = current.children.entry(byte).or_insert_with(TrieNode::new);
+        }
+
+        let old = current.value.replace(value);
+        if old.is_none() {
+            self.length += 1;
+        }
+        old
+    }
+
+    /// Retrieves a reference to the value stored under `key`.
+    /// This operation runs in O(k) time, where k is the length of the key.
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Option<&V> {
+        self.find_node(key.as_ref())?.value.as_ref()
+    }
+
+    /// Retrieves a mutable reference to the value stored under `key`.
+    pub fn get_mut<K: AsRef<[u8]>>(&mut self, key: K) -> Option<&mut V> {
+        let mut current = &mut self.root;
+        for byte in key.as_ref() {
+            current = current.children.get_mut(byte)?;
+        }
+        current.value.as_mut()
+    }
+
+    /// Returns `true` if the trie holds a value for `key`.
+    pub fn contains_key<K: AsRef<[u8]>>(&self, key: K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes `key` from the trie and returns its value.
+    ///
+    /// Nodes left without a value or children are pruned on the way back up, so removing a
+    /// key frees the branch that only it was using.
+    pub fn remove<K: AsRef<[u8]>>(&mut self, key: K) -> Option<V> {
+        let removed = Self::remove_from(&mut self.root, key.as_ref());
+        if removed.is_some() {
+            self.length -= 1;
+        }
+        removed
+    }
+
+    fn remove_from(node: &mut TrieNode<V>, key: &[u8]) -> Option<V> {
+        let Some((first, rest)) = key.split_first() else {
+            return node.value.take();
+        };
+
+        let child = node.children.get_mut(first)?;
+        let removed = Self::remove_from(child, rest);
+        if child.value.is_none() && child.children.is_empty() {
+            node.children.remove(first);
+        }
+        removed
+    }
+
+    /// Returns an iterator over all entries in lexicographic key order.
+    pub fn iter(&self) -> Iter<'_, V> {
+        self.starts_with("")
+    }
+
+    /// Returns all keys stored in the trie, in lexicographic order.
+    pub fn keys(&self) -> Vec<Vec<u8>> {
+        self.iter().map(|(key, _)| key).collect()
+    }
+
+    /// Returns an iterator over the entries whose keys start with `prefix`.
+    ///
+    /// Finding the prefix node takes O(p) time; each entry after that is produced lazily.
+    pub fn starts_with<K: AsRef<[u8]>>(&self, prefix: K) -> Iter<'_, V> {
+        let prefix = prefix.as_ref();
+        match self.find_node(prefix) {
+            Some(node) => Iter {
+                stack: vec![node.children.iter()],
+                key: prefix.to_vec(),
+                pending: node.value.as_ref(),
+            },
+            None => Iter {
+                stack: Vec::new(),
+                key: Vec::new(),
+                pending: None,
+            },
+        }
+    }
+
+    /// Finds the longest key in the trie that is a prefix of `query`.
+    ///
+    /// This is the lookup a router or an IP forwarding table performs.
+    ///
+    /// # Returns
+    ///
+    /// The matching prefix of `query` and its value, or `None` if no key is a prefix.
+    pub fn longest_prefix_match<'q>(&self, query: &'q [u8]) -> Option<(&'q [u8], &V)> {
+        let mut current = &self.root;
+        let mut best = current.value.as_ref().map(|value| (0, value));
+
+        for (i, byte) in query.iter().enumerate() {
+            match current.children.get(byte) {
+                Some(child) => current = child,
+                None => break,
+            }
+            if let Some(value) = &current.value {
+                best = Some((i + 1, value));
+            }
+        }
+
+        best.map(|(length, value)| (&query[..length], value))
+    }
+
+    /// Returns up to `k` entries starting with `prefix`, ranked by their stored weight.
+    ///
+    /// A min-heap of size `k` keeps the best candidates seen so far, so this runs in
+    /// O(m log k) time for m matching keys.
+    pub fn autocomplete<K: AsRef<[u8]>>(&self, prefix: K, k: usize) -> Vec<(Vec<u8>, &V)>
+    where
+        V: Ord,
+    {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        // Ties on weight go to the lexicographically smaller key.
+        let mut heap = BinaryHeap::new();
+        for (key, value) in self.starts_with(prefix) {
+            heap.push((Reverse(value), key));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|(Reverse(value), key)| (key, value))
+            .collect()
+    }
+}
+
+fn main() {
+    let mut trie = Trie::new();
+    for (word, weight) in [
+        ("car", 40),
+        ("card", 15),
+        ("care", 25),
+        ("careful", 30),
+        ("cart", 5),
+        ("cat", 50),
+        ("dog", 20),
+    ] {
+        trie.set(word, weight);
+    }
+    trie.set("cart", 10); // Updates the weight in place.
+    *trie.get_mut("card").unwrap() += 1;
+
+    println!("Words stored: {}", trie.len());
+    println!("Weight of 'care': {:?}", trie.get("care"));
+    println!("Contains 'ca': {}", trie.contains_key(b"ca"));
+
+    let completions: Vec<String> = trie
+        .starts_with("car")
+        .map(|(key, _)| String::from_utf8_lossy(&key).into_owned())
+        .collect();
+    println!("Words starting with 'car': {:?}", completions);
+
+    for (key, weight) in trie.autocomplete("ca", 3) {
+        println!(
+            "Top suggestion: {} ({})",
+            String::from_utf8_lossy(&key),
+            weight
+        );
+    }
+
+    println!("Removed 'careful': {:?}", trie.remove("careful"));
+    println!(
+        "All words: {:?}",
+        trie.keys()
+            .iter()
+            .map(|key| String::from_utf8_lossy(key))
+            .collect::<Vec<_>>()
+    );
+
+    let mut routes = Trie::new();
+    routes.set("/", "index");
+    routes.set("/api", "api root");
+    routes.set("/api/users", "users");
+    if let Some((prefix, handler)) = routes.longest_prefix_match(b"/api/users/42") {
+        println!(
+            "Route for /api/users/42: {} -> {}",
+            String::from_utf8_lossy(prefix),
+            handler
+        );
+    }
+    println!("Routes empty: {}", routes.is_empty());
+}
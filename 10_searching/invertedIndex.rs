@@ -0,0 +1,361 @@
+/*
+  linearSearch.rs scans every item to answer a single question. A search engine flips that
+  around: it reads every document once, up front, and records for each word the documents it
+  appears in (its "posting list"). A query then only touches the posting lists of its words.
+
+    "the hydra"        ->  the:   [0, 1, 2]
+    "the minotaur"         hydra: [0]
+    "the hydra lives"      ...
+
+  Posting lists are kept sorted by document id, so AND and OR queries are a merge-style walk
+  over two sorted lists, just like merge_sorted_arrays in 01_arrays. Matches are ranked with
+  BM25, and the whole index can be written to disk and read back.
+*/
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Error, ErrorKind, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"IDX1";
+
+/// BM25 term-frequency saturation.
+const K1: f64 = 1.2;
+/// BM25 document-length normalization.
+const B: f64 = 0.75;
+
+/// How the terms of a query are combined.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum QueryMode {
+    /// Every term must appear in the document.
+    And,
+    /// At least one term must appear in the document.
+    Or,
+}
+
+/// One entry of a posting list: a document and how often the term occurs in it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Posting {
+    doc_id: u32,
+    term_frequency: u32,
+}
+
+/// An inverted index over a growing collection of documents.
+#[derive(Debug, Default, PartialEq)]
+struct InvertedIndex {
+    documents: Vec<String>,
+    /// The number of tokens in each document, indexed by document id.
+    doc_lengths: Vec<u32>,
+    /// Posting lists, each sorted by document id.
+    postings: HashMap<String, Vec<Posting>>,
+}
+
+/// Splits text into lowercase alphanumeric tokens.
+///
+/// # Arguments
+///
+/// * `text` - The text to tokenize.
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_lowercase())
+        .collect()
+}
+
+/// Returns the documents that appear in both sorted lists.
+/// This runs in O(n + m) time by walking both lists once.
+fn intersect(list1: &[u32], list2: &[u32]) -> Vec<u32> {
+    let mut result = Vec::new();
+    let mut i = 0;
+    let mut j = 0;
+
+    while i < list1.len() && j < list2.len() {
+        if list1[i] < list2[j] {
+            i += 1;
+        } else if list1[i] > list2[j] {
+            j += 1;
+        } else {
+            result.push(list1[i]);
+            i += 1;
+            j += 1;
+        }
+    }
+
+    result
+}
+
+/// Returns the documents that appear in either sorted list, without duplicates.
+/// This runs in O(n + m) time by walking both lists once.
+fn union(list1: &[u32], list2: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(list1.len() + list2.len());
+    let mut i = 0;
+    let mut j = 0;
+
+    while i < list1.len() || j < list2.len() {
+        if j >= list2.len() || (i < list1.len() && list1[i] < list2[j]) {
+            result.push(list1[i]);
+            i += 1;
+        } else if i >= list1.len() || list2[j] < list1[i] {
+            result.push(list2[j]);
+            j += 1;
+        } else {
+            result.push(list1[i]);
+            i += 1;
+            j += 1;
+        }
+    }
+
+    result
+}
+
+impl InvertedIndex {
+    /// Creates an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a document to the index and returns its id.
+    ///
+    /// Ids are handed out in increasing order, so appending to each posting list keeps it
+    /// sorted without any extra work.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The document text.
+    pub fn add_document(&mut self, text: &str) -> u32 {
+        let doc_id = self.documents.len() as u32;
+        let tokens = tokenize(text);
+
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for token in &tokens {
+            *counts.entry(token.clone()).or_insert(0) += 1;
+        }
+        for (term, term_frequency) in counts {
+            self.postings.entry(term).or_default().push(Posting {
+                doc_id,
+                term_frequency,
+            });
+        }
+
+        self.documents.push(text.to_string());
+        self.doc_lengths.push(tokens.len() as u32);
+        doc_id
+    }
+
+    /// Returns the text of a document, if it exists.
+    pub fn document(&self, doc_id: u32) -> Option<&str> {
+        self.documents.get(doc_id as usize).map(String::as_str)
+    }
+
+    /// Returns the ids of the documents containing `term`, in increasing order.
+    fn doc_ids(&self, term: &str) -> Vec<u32> {
+        self.postings
+            .get(term)
+            .map(|list| list.iter().map(|p| p.doc_id).collect())
+            .unwrap_or_default()
+    }
+
+    /// Finds the documents matching a query without ranking them.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Free text; it is tokenized the same way as documents.
+    /// * `mode` - Whether all terms or any term must match.
+    ///
+    /// # Returns
+    ///
+    /// The matching document ids in increasing order.
+    pub fn matching(&self, query: &str, mode: QueryMode) -> Vec<u32> {
+        let mut terms = tokenize(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        // Intersecting the shortest lists first keeps the intermediate results small.
+        if mode == QueryMode::And {
+            terms.sort_by_key(|term| self.postings.get(term).map_or(0, Vec::len));
+        }
+
+        let mut result = self.doc_ids(&terms[0]);
+        for term in &terms[1..] {
+            let list = self.doc_ids(term);
+            result = match mode {
+                QueryMode::And => intersect(&result, &list),
+                QueryMode::Or => union(&result, &list),
+            };
+        }
+        result
+    }
+
+    /// Scores a document against a set of query terms with BM25.
+    fn bm25(&self, doc_id: u32, terms: &[String]) -> f64 {
+        let n = self.documents.len() as f64;
+        let total_length: u64 = self.doc_lengths.iter().map(|&len| len as u64).sum();
+        let average_length = total_length as f64 / n;
+        let length = self.doc_lengths[doc_id as usize] as f64;
+
+        terms
+            .iter()
+            .filter_map(|term| self.postings.get(term))
+            .map(|list| {
+                let tf = match list.binary_search_by_key(&doc_id, |p| p.doc_id) {
+                    Ok(index) => list[index].term_frequency as f64,
+                    Err(_) => return 0.0,
+                };
+                let df = list.len() as f64;
+                let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+                idf * tf * (K1 + 1.0) / (tf + K1 * (1.0 - B + B * length / average_length))
+            })
+            .sum()
+    }
+
+    /// Searches the index and ranks the matches by BM25 score.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Free text; it is tokenized the same way as documents.
+    /// * `mode` - Whether all terms or any term must match.
+    ///
+    /// # Returns
+    ///
+    /// `(doc_id, score)` pairs, best match first.
+    pub fn search(&self, query: &str, mode: QueryMode) -> Vec<(u32, f64)> {
+        let mut terms = tokenize(query);
+        terms.sort();
+        terms.dedup();
+
+        let mut results: Vec<(u32, f64)> = self
+            .matching(query, mode)
+            .into_iter()
+            .map(|doc_id| (doc_id, self.bm25(doc_id, &terms)))
+            .collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        results
+    }
+
+    /// Writes the index to `path` in a compact little-endian binary format.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+
+        write_u32(&mut writer, self.documents.len() as u32)?;
+        for (text, &length) in self.documents.iter().zip(&self.doc_lengths) {
+            write_bytes(&mut writer, text.as_bytes())?;
+            write_u32(&mut writer, length)?;
+        }
+
+        // Sort the terms so the same index always produces the same file.
+        let mut terms: Vec<&String> = self.postings.keys().collect();
+        terms.sort();
+        write_u32(&mut writer, terms.len() as u32)?;
+        for term in terms {
+            let list = &self.postings[term];
+            write_bytes(&mut writer, term.as_bytes())?;
+            write_u32(&mut writer, list.len() as u32)?;
+            for posting in list {
+                write_u32(&mut writer, posting.doc_id)?;
+                write_u32(&mut writer, posting.term_frequency)?;
+            }
+        }
+
+        writer.flush()
+    }
+
+    /// Reads an index previously written by `save`.
+    ///
+    /// # Returns
+    ///
+    /// The index, or an `InvalidData` error if the file is not an index, refers to an unknown
+    /// document, or has a posting list whose document ids are not strictly increasing.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "not an index file"));
+        }
+
+        let mut index = InvertedIndex::new();
+        for _ in 0..read_u32(&mut reader)? {
+            index.documents.push(read_string(&mut reader)?);
+            index.doc_lengths.push(read_u32(&mut reader)?);
+        }
+
+        for _ in 0..read_u32(&mut reader)? {
+            let term = read_string(&mut reader)?;
+            let count = read_u32(&mut reader)?;
+            let mut list = Vec::new();
+            for _ in 0..count {
+                let doc_id = read_u32(&mut reader)?;
+                let term_frequency = read_u32(&mut reader)?;
+                if doc_id as usize >= index.documents.len() {
+                    return Err(Error::new(ErrorKind::InvalidData, "unknown document id"));
+                }
+                if list
+                    .last()
+                    .is_some_and(|last: &Posting| last.doc_id >= doc_id)
+                {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "posting list has unsorted or duplicate document ids",
+                    ));
+                }
+                list.push(Posting {
+                    doc_id,
+                    term_frequency,
+                });
+            }
+            index.postings.insert(term, list);
+        }
+
+        Ok(index)
+    }
+}
+
+fn write_u32<W: Write>(writer: &mut W, value: u32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    write_u32(writer, bytes.len() as u32)?;
+    writer.write_all(bytes)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_string<R: Read>(reader: &mut R) -> io::Result<String> {
+    let mut bytes = vec![0; read_u32(reader)? as usize];
+    reader.read_exact(&mut bytes)?;
+    String::from_utf8(bytes).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+}
+
+fn main() {
+    let mut index = InvertedIndex::new();
+    index.add_document("The Centaur is half human and half horse.");
+    index.add_document("Godzilla rises from the sea and flattens Tokyo.");
+    index.add_document("Mosura, the giant moth, battles Godzilla over Tokyo.");
+    index.add_document("The Minotaur waits in the labyrinth, half man and half bull.");
+    index.add_document("The Hydra grows two heads for every head it loses.");
+    index.add_document("Nessie hides in a Scottish loch.");
+
+    for (query, mode) in [
+        ("godzilla tokyo", QueryMode::And),
+        ("half horse", QueryMode::Or),
+        ("hydra nessie", QueryMode::Or),
+    ] {
+        println!("{:?} {:?}:", mode, query);
+        for (doc_id, score) in index.search(query, mode) {
+            println!("  {:.3}  {}", score, index.document(doc_id).unwrap());
+        }
+    }
+
+    let path = std::env::temp_dir().join("inverted_index.idx");
+    index.save(&path).expect("failed to save the index");
+    let loaded = InvertedIndex::load(&path).expect("failed to load the index");
+    println!("Index survives a round trip to disk: {}", loaded == index);
+    let _ = std::fs::remove_file(path);
+}
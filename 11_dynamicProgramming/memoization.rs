@@ -1,47 +1,43 @@
-use std::collections::HashMap;
-use std::sync::{Mutex, Once};
+use std::sync::OnceLock;
+
+#[allow(dead_code)]
+#[path = "../02_hashTables/shardedHashTable.rs"]
+mod sharded_hash_table;
+
+use sharded_hash_table::hash_table::HashTable;
+use sharded_hash_table::ShardedHashTable;
 
 // Simple function to add 80 to a number
 fn add_to_80(n: i32) -> i32 {
     n + 80
 }
 
-// Use `Once` and `Mutex` for safe static initialization
-static mut CACHE: Option<Mutex<HashMap<i32, i32>>> = None;
-static INIT: Once = Once::new();
+// The global cache is split into independently locked shards, so threads working on
+// different keys rarely wait on each other.
+// `OnceLock` initializes the global cache safely on first use, without `static mut`
+static CACHE: OnceLock<ShardedHashTable<i32, i32>> = OnceLock::new();
 
-fn get_cache() -> &'static Mutex<HashMap<i32, i32>> {
-    unsafe {
-        INIT.call_once(|| {
-            CACHE = Some(Mutex::new(HashMap::new()));
-        });
-        CACHE.as_ref().unwrap()
-    }
+fn get_cache() -> &'static ShardedHashTable<i32, i32> {
+    CACHE.get_or_init(|| ShardedHashTable::new(16))
 }
 
 fn memoize_add_to_80_v1(n: i32) -> i32 {
-    let cache = get_cache();
-    let mut cache_lock = cache.lock().unwrap();
-    if let Some(&value) = cache_lock.get(&n) {
-        value
-    } else {
+    get_cache().get_or_insert_with(n, || {
         println!("long time");
-        let answer = n + 80;
-        cache_lock.insert(n, answer);
-        answer
-    }
+        n + 80
+    })
 }
 
 // Second version using a closure to encapsulate the cache, avoiding global state
 fn memoize_add_to_80_v2() -> Box<dyn FnMut(i32) -> i32> {
-    let mut cache = HashMap::new();
+    let mut cache = HashTable::new(0);
     Box::new(move |n| {
         if let Some(&value) = cache.get(&n) {
             value
         } else {
             println!("long time");
             let answer = n + 80;
-            cache.insert(n, answer);
+            cache.set(n, answer);
             answer
         }
     })
@@ -52,10 +48,17 @@ fn main() {
 
     // Example usage of the first version
     println!("1: {}", memoize_add_to_80_v1(6));
-    // println!("cache {:?}", unsafe { &CACHE }); // Uncomment to view cache state
     // println!("-----------");
     println!("2: {}", memoize_add_to_80_v1(6));
 
+    // The cache is shared, so other threads reuse the answer instead of recomputing it
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| memoize_add_to_80_v1(7));
+        }
+    });
+    println!("3: {}", memoize_add_to_80_v1(7));
+
     // Example usage of the second version
     let mut memoized = memoize_add_to_80_v2();
     println!("1: {}", memoized(6));
@@ -0,0 +1,340 @@
+/*
+  A block-sorting compressor in the spirit of bzip2.
+
+  Each block of input goes through three reversible stages:
+
+    1. Burrows-Wheeler transform: sort every rotation of the block and keep the last column.
+       Equal contexts end up next to each other, so the output is full of runs.
+    2. Move-to-front: replace each byte with its position in a recently-used list. Runs turn
+       into runs of zeros.
+    3. Run-length encoding: collapse those runs.
+
+  Blocks are stored in a small container:
+
+    "BWT1" | block size: u32 | per block: [length: u32][primary index: u32][payload length: u32][payload]
+
+  All integers are little-endian. The primary index is the row of the sorted rotations that
+  holds the end-of-block sentinel, which the inverse transform needs to find its way back.
+
+  Usage:
+    burrowsWheelerTransform compress <input> <output> [block size]
+    burrowsWheelerTransform decompress <input> <output>
+
+  Compiled as `bwt-compress` or `bwt-decompress`, the binary picks the mode from its own name:
+    rustc burrowsWheelerTransform.rs -o bwt-compress
+    bwt-compress <input> <output> [block size]
+*/
+
+use std::env;
+use std::fs;
+use std::io::{self, Error, ErrorKind};
+use std::path::Path;
+
+#[allow(dead_code)]
+#[path = "suffixArray.rs"]
+mod suffix_array;
+
+use suffix_array::suffix_array_doubling;
+
+const MAGIC: &[u8; 4] = b"BWT1";
+const DEFAULT_BLOCK_SIZE: usize = 1 << 18;
+
+/// Applies the Burrows-Wheeler transform to `block`.
+///
+/// The block is treated as if it ended with a sentinel smaller than every byte. The sentinel
+/// itself is not written out; its row is returned as the primary index instead.
+///
+/// # Arguments
+///
+/// * `block` - The bytes to transform.
+///
+/// # Returns
+///
+/// The last column of the sorted rotations (without the sentinel) and the primary index.
+fn bwt_forward(block: &[u8]) -> (Vec<u8>, usize) {
+    let n = block.len();
+    if n == 0 {
+        return (Vec::new(), 0);
+    }
+    let sa = suffix_array_doubling(block);
+
+    // The rotation starting at the sentinel sorts first and ends with the last byte.
+    let mut last_column = Vec::with_capacity(n);
+    last_column.push(block[n - 1]);
+    let mut primary = 0;
+    for (row, &start) in sa.iter().enumerate() {
+        if start == 0 {
+            primary = row + 1;
+        } else {
+            last_column.push(block[start - 1]);
+        }
+    }
+
+    (last_column, primary)
+}
+
+/// Reverses the Burrows-Wheeler transform with the last-to-first mapping.
+///
+/// # Arguments
+///
+/// * `last_column` - The transformed bytes, without the sentinel.
+/// * `primary` - The row that holds the sentinel.
+///
+/// # Returns
+///
+/// The original block, or `None` if `primary` is out of range or the input is not the
+/// transform of any block.
+fn bwt_inverse(last_column: &[u8], primary: usize) -> Option<Vec<u8>> {
+    let n = last_column.len();
+    if n == 0 {
+        return Some(Vec::new());
+    }
+    if primary == 0 || primary > n {
+        return None;
+    }
+
+    // Re-insert the sentinel as symbol 0 and shift every byte up by one.
+    let symbol = |row: usize| match row.cmp(&primary) {
+        std::cmp::Ordering::Less => last_column[row] as usize + 1,
+        std::cmp::Ordering::Equal => 0,
+        std::cmp::Ordering::Greater => last_column[row - 1] as usize + 1,
+    };
+
+    // first_row[c] is the first row of the sorted rotations that starts with symbol c.
+    let mut first_row = [0usize; 258];
+    for row in 0..=n {
+        first_row[symbol(row) + 1] += 1;
+    }
+    for c in 1..first_row.len() {
+        first_row[c] += first_row[c - 1];
+    }
+
+    let mut seen = [0usize; 257];
+    let mut lf = vec![0; n + 1];
+    for (row, next) in lf.iter_mut().enumerate() {
+        let c = symbol(row);
+        *next = first_row[c] + seen[c];
+        seen[c] += 1;
+    }
+
+    // Row 0 starts with the sentinel, so its last column holds the final byte of the block.
+    // A damaged last column or primary index can reach the sentinel early, or split the rows
+    // into several cycles so that the walk never reaches it.
+    let mut block = vec![0; n];
+    let mut row = 0;
+    for slot in block.iter_mut().rev() {
+        let c = symbol(row);
+        if c == 0 {
+            return None;
+        }
+        *slot = (c - 1) as u8;
+        row = lf[row];
+    }
+    (row == primary).then_some(block)
+}
+
+/// Replaces every byte with its index in a list of bytes ordered by most recent use.
+fn move_to_front_encode(input: &[u8]) -> Vec<u8> {
+    let mut order: Vec<u8> = (0..=255).collect();
+    input
+        .iter()
+        .map(|&byte| {
+            let index = order.iter().position(|&b| b == byte).unwrap();
+            order.remove(index);
+            order.insert(0, byte);
+            index as u8
+        })
+        .collect()
+}
+
+/// Reverses `move_to_front_encode`.
+fn move_to_front_decode(input: &[u8]) -> Vec<u8> {
+    let mut order: Vec<u8> = (0..=255).collect();
+    input
+        .iter()
+        .map(|&index| {
+            let byte = order.remove(index as usize);
+            order.insert(0, byte);
+            byte
+        })
+        .collect()
+}
+
+/// Run-length encodes `input`.
+///
+/// A run is written as the byte twice followed by a count of further repeats (0 to 255),
+/// so isolated bytes cost nothing extra.
+fn run_length_encode(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        let byte = input[i];
+        let mut run = 1;
+        while i + run < input.len() && input[i + run] == byte && run < 257 {
+            run += 1;
+        }
+
+        output.push(byte);
+        if run >= 2 {
+            output.push(byte);
+            output.push((run - 2) as u8);
+        }
+        i += run;
+    }
+    output
+}
+
+/// Reverses `run_length_encode`, or returns `None` if a run is missing its count.
+fn run_length_decode(input: &[u8]) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        let byte = input[i];
+        if i + 1 < input.len() && input[i + 1] == byte {
+            let extra = *input.get(i + 2)? as usize;
+            output.extend(std::iter::repeat_n(byte, extra + 2));
+            i += 3;
+        } else {
+            output.push(byte);
+            i += 1;
+        }
+    }
+    Some(output)
+}
+
+/// Compresses `input` into the container format, one block of `block_size` bytes at a time.
+fn compress(input: &[u8], block_size: usize) -> Vec<u8> {
+    let mut output = Vec::new();
+    output.extend_from_slice(MAGIC);
+    output.extend_from_slice(&(block_size as u32).to_le_bytes());
+
+    for block in input.chunks(block_size) {
+        let (last_column, primary) = bwt_forward(block);
+        let payload = run_length_encode(&move_to_front_encode(&last_column));
+
+        output.extend_from_slice(&(block.len() as u32).to_le_bytes());
+        output.extend_from_slice(&(primary as u32).to_le_bytes());
+        output.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        output.extend_from_slice(&payload);
+    }
+    output
+}
+
+/// Reads a little-endian `u32` at `*pos` and advances past it.
+fn read_u32(input: &[u8], pos: &mut usize) -> io::Result<usize> {
+    let bytes = input
+        .get(*pos..*pos + 4)
+        .ok_or_else(|| invalid_data("truncated header"))?;
+    *pos += 4;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Decompresses a container produced by `compress`.
+fn decompress(input: &[u8]) -> io::Result<Vec<u8>> {
+    if !input.starts_with(MAGIC) {
+        return Err(invalid_data("not a BWT1 container"));
+    }
+    let mut pos = MAGIC.len();
+    let block_size = read_u32(input, &mut pos)?;
+
+    let mut output = Vec::new();
+    while pos < input.len() {
+        let length = read_u32(input, &mut pos)?;
+        let primary = read_u32(input, &mut pos)?;
+        let payload_length = read_u32(input, &mut pos)?;
+        if length > block_size {
+            return Err(invalid_data("block larger than the declared block size"));
+        }
+
+        let payload = input
+            .get(pos..pos + payload_length)
+            .ok_or_else(|| invalid_data("truncated block"))?;
+        pos += payload_length;
+
+        let last_column = run_length_decode(payload)
+            .map(|mtf| move_to_front_decode(&mtf))
+            .filter(|column| column.len() == length)
+            .ok_or_else(|| invalid_data("corrupt block payload"))?;
+        let block = bwt_inverse(&last_column, primary)
+            .ok_or_else(|| invalid_data("primary index out of range"))?;
+        output.extend_from_slice(&block);
+    }
+    Ok(output)
+}
+
+fn run(mode: &str, args: &[String]) -> io::Result<()> {
+    let usage = || Error::new(ErrorKind::InvalidInput, "expected <input> <output>");
+    let (input_path, output_path) = match args {
+        [input, output, ..] => (input, output),
+        _ => return Err(usage()),
+    };
+    let input = fs::read(input_path)?;
+
+    let output = match mode {
+        "compress" => {
+            let block_size = match args.get(2) {
+                Some(size) => size
+                    .parse()
+                    .ok()
+                    .filter(|&size: &usize| size > 0 && size <= u32::MAX as usize)
+                    .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "invalid block size"))?,
+                None => DEFAULT_BLOCK_SIZE,
+            };
+            compress(&input, block_size)
+        }
+        _ => decompress(&input)?,
+    };
+
+    fs::write(output_path, &output)?;
+    println!("{}: {} bytes -> {} bytes", mode, input.len(), output.len());
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let program = args
+        .first()
+        .and_then(|arg| Path::new(arg).file_stem())
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+
+    let result = match (program, args.get(1).map(String::as_str)) {
+        ("bwt-compress", _) => run("compress", &args[1..]),
+        ("bwt-decompress", _) => run("decompress", &args[1..]),
+        (_, Some(mode @ ("compress" | "decompress"))) => run(mode, &args[2..]),
+        (_, Some(_)) => Err(Error::new(
+            ErrorKind::InvalidInput,
+            "usage: compress|decompress <input> <output> [block size]",
+        )),
+        (_, None) => {
+            // No arguments: demonstrate each stage on a small example.
+            let text = b"banana_bandana_banana";
+            let (last_column, primary) = bwt_forward(text);
+            println!(
+                "BWT: {:?} (primary index {})",
+                String::from_utf8_lossy(&last_column),
+                primary
+            );
+            println!("Move-to-front: {:?}", move_to_front_encode(&last_column));
+
+            let packed = compress(text, 8);
+            let unpacked = decompress(&packed).unwrap();
+            println!(
+                "Round trip: {:?} ({} bytes -> {} bytes)",
+                String::from_utf8_lossy(&unpacked),
+                text.len(),
+                packed.len()
+            );
+            Ok(())
+        }
+    };
+
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
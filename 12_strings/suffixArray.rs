@@ -0,0 +1,433 @@
+/*
+  A suffix array lists the starting positions of every suffix of a string in sorted order.
+  Paired with the LCP (longest common prefix) array, it answers a whole family of substring
+  questions with nothing more than a sort and a binary search:
+
+    text   = "banana"
+    SA     = [5, 3, 1, 0, 4, 2]   ->  a, ana, anana, banana, na, nana
+    LCP    = [0, 1, 3, 0, 0, 2]
+
+  Two constructions are shown: prefix doubling, which repeatedly radix sorts rank pairs with
+  the counting sort from 09_sorting, and SA-IS, which builds the array in linear time.
+*/
+
+use std::cmp::Ordering;
+use std::ops::Range;
+
+const EMPTY: usize = usize::MAX;
+
+/// Stable counting sort of `items` by `key`, where every key is in `0..=max_key`.
+///
+/// # Arguments
+///
+/// * `items` - The suffix positions to sort.
+/// * `max_key` - The largest key `key` can return.
+/// * `key` - Maps a suffix position to its sort key.
+fn counting_sort_by<F>(items: &[usize], max_key: usize, key: F) -> Vec<usize>
+where
+    F: Fn(usize) -> usize,
+{
+    let mut count = vec![0; max_key + 2];
+    for &item in items {
+        count[key(item) + 1] += 1;
+    }
+    for i in 1..count.len() {
+        count[i] += count[i - 1];
+    }
+
+    let mut sorted = vec![0; items.len()];
+    for &item in items {
+        let slot = &mut count[key(item)];
+        sorted[*slot] = item;
+        *slot += 1;
+    }
+    sorted
+}
+
+/// Builds a suffix array by prefix doubling in O(n log n) time.
+///
+/// Every round sorts the suffixes by their first `2k` characters, using the ranks of the
+/// first `k` characters as a pair of radix digits.
+///
+/// # Arguments
+///
+/// * `text` - The bytes to index.
+///
+/// # Returns
+///
+/// The starting positions of all suffixes of `text` in lexicographic order.
+pub fn suffix_array_doubling(text: &[u8]) -> Vec<usize> {
+    let n = text.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    // Rank 0 is reserved for "past the end of the text", so it sorts before any character.
+    let mut rank: Vec<usize> = text.iter().map(|&b| b as usize + 1).collect();
+    let mut sa: Vec<usize> = (0..n).collect();
+    let mut max_rank = 256;
+    let mut k = 1;
+
+    loop {
+        let second = |i: usize| if i + k < n { rank[i + k] } else { 0 };
+
+        // LSD radix sort: least significant digit first, then the most significant one.
+        sa = counting_sort_by(&sa, max_rank, second);
+        sa = counting_sort_by(&sa, max_rank, |i| rank[i]);
+
+        let mut new_rank = vec![0; n];
+        new_rank[sa[0]] = 1;
+        for w in 1..n {
+            let (prev, cur) = (sa[w - 1], sa[w]);
+            let differs = (rank[prev], second(prev)) != (rank[cur], second(cur));
+            new_rank[cur] = new_rank[prev] + differs as usize;
+        }
+        rank = new_rank;
+        max_rank = rank[sa[n - 1]];
+
+        if max_rank == n {
+            return sa;
+        }
+        k *= 2;
+    }
+}
+
+/// Builds a suffix array with the SA-IS algorithm in O(n) time.
+///
+/// # Arguments
+///
+/// * `text` - The bytes to index.
+///
+/// # Returns
+///
+/// The starting positions of all suffixes of `text` in lexicographic order.
+fn suffix_array_sais(text: &[u8]) -> Vec<usize> {
+    let symbols: Vec<usize> = text.iter().map(|&b| b as usize + 1).collect();
+    suffix_array_of_symbols(&symbols, 257)
+}
+
+/// Builds the suffix array of a string over the integer alphabet `1..alphabet_size`.
+///
+/// A unique sentinel `0` is appended for SA-IS and removed again from the result.
+fn suffix_array_of_symbols(symbols: &[usize], alphabet_size: usize) -> Vec<usize> {
+    let mut s = symbols.to_vec();
+    s.push(0);
+    let sa = sa_is(&s, alphabet_size);
+    sa[1..].to_vec()
+}
+
+/// The recursive core of SA-IS. `s` must end with a unique, smallest symbol.
+fn sa_is(s: &[usize], alphabet_size: usize) -> Vec<usize> {
+    let n = s.len();
+    if n == 1 {
+        return vec![0];
+    }
+
+    // Classify each suffix as S-type (smaller than its successor) or L-type.
+    let mut is_s = vec![false; n];
+    is_s[n - 1] = true;
+    for i in (0..n - 1).rev() {
+        is_s[i] = s[i] < s[i + 1] || (s[i] == s[i + 1] && is_s[i + 1]);
+    }
+    let is_lms = |i: usize| i > 0 && is_s[i] && !is_s[i - 1];
+
+    let mut counts = vec![0; alphabet_size];
+    for &c in s {
+        counts[c] += 1;
+    }
+
+    // Sort the LMS substrings by inducing from LMS positions in text order.
+    let lms: Vec<usize> = (1..n).filter(|&i| is_lms(i)).collect();
+    let sa = induce_sort(s, &is_s, &counts, &lms);
+
+    // Name each LMS substring by its rank among the distinct LMS substrings.
+    let sorted_lms: Vec<usize> = sa.into_iter().filter(|&p| is_lms(p)).collect();
+    let mut names = vec![EMPTY; n];
+    let mut name = 0;
+    names[sorted_lms[0]] = 0;
+    for w in sorted_lms.windows(2) {
+        if !lms_substrings_equal(s, &is_s, w[0], w[1]) {
+            name += 1;
+        }
+        names[w[1]] = name;
+    }
+
+    // If any two LMS substrings share a name, recurse on the reduced string to order them.
+    let sorted_lms = if name + 1 < lms.len() {
+        let reduced: Vec<usize> = lms.iter().map(|&p| names[p]).collect();
+        sa_is(&reduced, name + 1)
+            .into_iter()
+            .map(|i| lms[i])
+            .collect()
+    } else {
+        sorted_lms
+    };
+
+    induce_sort(s, &is_s, &counts, &sorted_lms)
+}
+
+/// Places the LMS suffixes at the ends of their buckets and induces the L- and S-type suffixes.
+fn induce_sort(s: &[usize], is_s: &[bool], counts: &[usize], lms: &[usize]) -> Vec<usize> {
+    let n = s.len();
+    let mut sa = vec![EMPTY; n];
+
+    let bucket_ends = || {
+        let mut sum = 0;
+        counts
+            .iter()
+            .map(|&c| {
+                sum += c;
+                sum
+            })
+            .collect::<Vec<usize>>()
+    };
+    let bucket_starts = || {
+        let mut sum = 0;
+        counts
+            .iter()
+            .map(|&c| {
+                sum += c;
+                sum - c
+            })
+            .collect::<Vec<usize>>()
+    };
+
+    let mut tails = bucket_ends();
+    for &p in lms.iter().rev() {
+        tails[s[p]] -= 1;
+        sa[tails[s[p]]] = p;
+    }
+
+    let mut heads = bucket_starts();
+    for i in 0..n {
+        let j = sa[i];
+        if j != EMPTY && j > 0 && !is_s[j - 1] {
+            sa[heads[s[j - 1]]] = j - 1;
+            heads[s[j - 1]] += 1;
+        }
+    }
+
+    let mut tails = bucket_ends();
+    for i in (0..n).rev() {
+        let j = sa[i];
+        if j != EMPTY && j > 0 && is_s[j - 1] {
+            tails[s[j - 1]] -= 1;
+            sa[tails[s[j - 1]]] = j - 1;
+        }
+    }
+
+    sa
+}
+
+/// Compares the LMS substrings starting at `a` and `b` symbol by symbol and type by type.
+fn lms_substrings_equal(s: &[usize], is_s: &[bool], a: usize, b: usize) -> bool {
+    let n = s.len();
+    if a == n - 1 || b == n - 1 {
+        return a == b;
+    }
+
+    let is_lms = |i: usize| is_s[i] && !is_s[i - 1];
+    let mut i = 0;
+    loop {
+        let a_end = i > 0 && is_lms(a + i);
+        let b_end = i > 0 && is_lms(b + i);
+        if a_end && b_end {
+            return true;
+        }
+        if a_end != b_end || s[a + i] != s[b + i] || is_s[a + i] != is_s[b + i] {
+            return false;
+        }
+        i += 1;
+    }
+}
+
+/// Builds the LCP array with Kasai's algorithm in O(n) time.
+///
+/// # Arguments
+///
+/// * `text` - The indexed text.
+/// * `sa` - The suffix array of `text`.
+///
+/// # Returns
+///
+/// A vector where entry `i` is the length of the longest common prefix of the suffixes at
+/// `sa[i - 1]` and `sa[i]`. Entry `0` is always `0`.
+fn lcp_array<T: Eq>(text: &[T], sa: &[usize]) -> Vec<usize> {
+    let n = text.len();
+    let mut rank = vec![0; n];
+    for (i, &p) in sa.iter().enumerate() {
+        rank[p] = i;
+    }
+
+    let mut lcp = vec![0; n];
+    let mut h = 0;
+    for i in 0..n {
+        if rank[i] == 0 {
+            h = 0;
+            continue;
+        }
+        let j = sa[rank[i] - 1];
+        while i + h < n && j + h < n && text[i + h] == text[j + h] {
+            h += 1;
+        }
+        lcp[rank[i]] = h;
+        // Dropping the first character of a suffix shortens its LCP by at most one.
+        h = h.saturating_sub(1);
+    }
+    lcp
+}
+
+/// Compares the suffix at `start` against `pattern`, looking only at the first
+/// `pattern.len()` bytes of the suffix.
+fn compare_prefix(text: &[u8], start: usize, pattern: &[u8]) -> Ordering {
+    let end = (start + pattern.len()).min(text.len());
+    text[start..end].cmp(pattern)
+}
+
+/// Finds the block of the suffix array whose suffixes start with `pattern`.
+///
+/// Uses a lower-bound and an upper-bound binary search, in the style of `binary_search`
+/// from 10_searching, so the lookup takes O(m log n) time for a pattern of length m.
+///
+/// # Arguments
+///
+/// * `text` - The indexed text.
+/// * `sa` - The suffix array of `text`.
+/// * `pattern` - The substring to look up.
+///
+/// # Returns
+///
+/// The range of suffix array indices whose suffixes begin with `pattern`.
+fn occurrence_range(text: &[u8], sa: &[usize], pattern: &[u8]) -> Range<usize> {
+    let bound = |stop_at_equal: bool| {
+        let mut start = 0;
+        let mut end = sa.len();
+        while start < end {
+            let mid = start + (end - start) / 2;
+            match compare_prefix(text, sa[mid], pattern) {
+                Ordering::Less => start = mid + 1,
+                Ordering::Equal if !stop_at_equal => start = mid + 1,
+                _ => end = mid,
+            }
+        }
+        start
+    };
+
+    bound(true)..bound(false)
+}
+
+/// Counts how many times `pattern` occurs in `text`, overlaps included.
+///
+/// # Arguments
+///
+/// * `text` - The indexed text.
+/// * `sa` - The suffix array of `text`.
+/// * `pattern` - The substring to count.
+fn count_occurrences(text: &[u8], sa: &[usize], pattern: &[u8]) -> usize {
+    occurrence_range(text, sa, pattern).len()
+}
+
+/// Finds the longest substring that occurs at least twice in `text`.
+///
+/// # Arguments
+///
+/// * `text` - The text to search.
+///
+/// # Returns
+///
+/// The leftmost-in-suffix-order longest repeated substring, or an empty slice if no
+/// character repeats.
+fn longest_repeated_substring(text: &[u8]) -> &[u8] {
+    let sa = suffix_array_sais(text);
+    let lcp = lcp_array(text, &sa);
+
+    match lcp
+        .iter()
+        .enumerate()
+        .max_by_key(|&(i, &len)| (len, usize::MAX - i))
+    {
+        Some((i, &len)) if len > 0 => &text[sa[i]..sa[i] + len],
+        _ => &[],
+    }
+}
+
+/// Finds the longest substring shared by `a` and `b`.
+///
+/// Both strings are joined around a separator that sorts below every byte, and the answer is
+/// the largest LCP between neighbouring suffixes that come from different strings.
+///
+/// # Arguments
+///
+/// * `a` - The first string.
+/// * `b` - The second string.
+///
+/// # Returns
+///
+/// A longest common substring, borrowed from `a`.
+fn longest_common_substring<'a>(a: &'a [u8], b: &[u8]) -> &'a [u8] {
+    let joined: Vec<usize> = a
+        .iter()
+        .map(|&c| c as usize + 2)
+        .chain(std::iter::once(1))
+        .chain(b.iter().map(|&c| c as usize + 2))
+        .collect();
+    let sa = suffix_array_of_symbols(&joined, 258);
+    let lcp = lcp_array(&joined, &sa);
+
+    let from_a = |p: usize| p < a.len();
+    let mut best = (0, 0);
+    for i in 1..sa.len() {
+        if from_a(sa[i - 1]) != from_a(sa[i]) && lcp[i] > best.1 {
+            let start = if from_a(sa[i]) { sa[i] } else { sa[i - 1] };
+            best = (start, lcp[i]);
+        }
+    }
+
+    &a[best.0..best.0 + best.1]
+}
+
+/// Counts the distinct non-empty substrings of `text`.
+///
+/// Each suffix contributes its length, minus the prefix it shares with the previous suffix
+/// in sorted order, since those substrings were already counted.
+///
+/// # Arguments
+///
+/// * `text` - The text to examine.
+fn distinct_substring_count(text: &[u8]) -> usize {
+    let n = text.len();
+    let sa = suffix_array_sais(text);
+    let lcp = lcp_array(text, &sa);
+    n * (n + 1) / 2 - lcp.iter().sum::<usize>()
+}
+
+fn main() {
+    let text = b"banana";
+
+    let sa = suffix_array_sais(text);
+    assert_eq!(sa, suffix_array_doubling(text));
+    println!("Suffix array of 'banana': {:?}", sa); // [5, 3, 1, 0, 4, 2]
+    println!("LCP array of 'banana': {:?}", lcp_array(text, &sa)); // [0, 1, 3, 0, 0, 2]
+
+    for pattern in ["ana", "na", "ban", "x"] {
+        println!(
+            "'{}' occurs {} time(s)",
+            pattern,
+            count_occurrences(text, &sa, pattern.as_bytes())
+        );
+    }
+
+    let sentence = b"to be or not to be, that is the question";
+    println!(
+        "Longest repeated substring: {:?}",
+        String::from_utf8_lossy(longest_repeated_substring(sentence))
+    );
+    println!(
+        "Longest common substring: {:?}",
+        String::from_utf8_lossy(longest_common_substring(b"xabcdey", b"zzbcdez"))
+    );
+    println!(
+        "Distinct substrings of 'banana': {}",
+        distinct_substring_count(text)
+    ); // 15
+}
@@ -15,6 +15,14 @@
   2 parameters - arrays - no size limit
   return true or false
 */
+use std::hash::Hash;
+
+#[allow(dead_code)]
+#[path = "02_hashTables/hashTableImplementation.rs"]
+mod hash_table;
+
+use hash_table::HashTable;
+
 /// Checks if two slices contain any common items using a nested loop approach.
 /// This function directly translates the nested loop logic from JavaScript to Rust,
 /// comparing each item in one array against all in the other.
@@ -75,6 +83,214 @@ fn contains_common_item3(arr1: &[&str], arr2: &[&str]) -> bool {
     arr1.iter().any(|item| arr2.contains(item))
 }
 
+/// Returns the items the two inputs have in common, as a multiset: an item that appears
+/// twice in one input and three times in the other is returned twice.
+/// Counts the first input in a hash table, then streams the second against it, so this runs in
+/// O(n + m) time and needs memory only for the distinct items of the first input.
+///
+/// # Arguments
+/// * `first` - Any iterable of items; put the smaller input here to save memory.
+/// * `second` - Any iterable of items.
+///
+/// # Returns
+/// * `Vec<T>` - The common items, in the order they appear in `second`.
+fn common_items<T, A, B>(first: A, second: B) -> Vec<T>
+where
+    T: Hash + Eq,
+    A: IntoIterator<Item = T>,
+    B: IntoIterator<Item = T>,
+{
+    let mut counts = HashTable::new(0);
+    for item in first {
+        *counts.entry(item).or_insert(0usize) += 1;
+    }
+
+    let mut common = Vec::new();
+    for item in second {
+        if let Some(count) = counts.get_mut(&item) {
+            if *count > 0 {
+                *count -= 1;
+                common.push(item);
+            }
+        }
+    }
+    common
+}
+
+/// Returns the common items of two sorted slices by walking both at once, like the merge step
+/// of merge sort. This runs in O(n + m) time and needs no extra memory besides the result.
+///
+/// # Arguments
+/// * `first` - A slice sorted in ascending order.
+/// * `second` - A slice sorted in ascending order.
+///
+/// # Returns
+/// * `Vec<T>` - The common items as a multiset, in ascending order.
+fn common_items_sorted<T: Ord + Clone>(first: &[T], second: &[T]) -> Vec<T> {
+    let mut common = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < first.len() && j < second.len() {
+        match first[i].cmp(&second[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                common.push(first[i].clone());
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    common
+}
+
+/// Returns the common items of a short sorted slice and a much longer one.
+///
+/// For each item of `small`, gallops through `large` from where the previous search ended:
+/// it probes 1, 2, 4, 8, ... positions ahead until it overshoots, then binary searches the last
+/// gap. Finding an item d positions ahead costs O(log d), so the whole walk runs in
+/// O(m log(n / m)) time for m items in `small` and n in `large`, far below O(n + m) when m is tiny.
+///
+/// # Arguments
+/// * `small` - A slice sorted in ascending order.
+/// * `large` - A slice sorted in ascending order.
+///
+/// # Returns
+/// * `Vec<T>` - The common items as a multiset, in ascending order.
+fn common_items_galloping<T: Ord + Clone>(small: &[T], large: &[T]) -> Vec<T> {
+    let mut common = Vec::new();
+    let mut start = 0;
+    for item in small {
+        let mut step = 1;
+        while start + step < large.len() && large[start + step] < *item {
+            step *= 2;
+        }
+        let end = (start + step + 1).min(large.len());
+        start += large[start..end].partition_point(|candidate| candidate < item);
+        if start == large.len() {
+            break;
+        }
+        if large[start] == *item {
+            common.push(item.clone());
+            start += 1;
+        }
+    }
+    common
+}
+
+/// Returns the items common to every input, as a multiset: each item appears as often as in
+/// the input where it is rarest.
+/// This runs in O(total length) time, and needs memory only for the distinct items of the
+/// first input, so the smallest input should come first.
+///
+/// # Arguments
+/// * `inputs` - Any number of iterables of items.
+///
+/// # Returns
+/// * `Vec<T>` - The common items, in the order they first appear in the first input.
+fn common_items_n_way<T, I, L>(inputs: L) -> Vec<T>
+where
+    T: Hash + Eq + Clone,
+    I: IntoIterator<Item = T>,
+    L: IntoIterator<Item = I>,
+{
+    let mut inputs = inputs.into_iter();
+    let Some(first) = inputs.next() else {
+        return Vec::new();
+    };
+
+    let mut order = Vec::new();
+    let mut counts = HashTable::new(0);
+    for item in first {
+        let count = counts.entry(item.clone()).or_insert(0usize);
+        if *count == 0 {
+            order.push(item);
+        }
+        *count += 1;
+    }
+
+    for input in inputs {
+        let mut seen = HashTable::new(0);
+        for item in input {
+            if counts.contains_key(&item) {
+                *seen.entry(item).or_insert(0usize) += 1;
+            }
+        }
+        counts.retain(|item, count| {
+            *count = (*count).min(seen.get(item).copied().unwrap_or(0));
+            *count > 0
+        });
+        if counts.is_empty() {
+            return Vec::new();
+        }
+    }
+
+    let mut common = Vec::new();
+    for item in order {
+        if let Some(&count) = counts.get(&item) {
+            common.extend(std::iter::repeat_n(item, count));
+        }
+    }
+    common
+}
+
+/// The ways `common_items_auto` can intersect two slices.
+#[derive(Debug, PartialEq)]
+enum Strategy {
+    Hash,
+    SortedMerge,
+    Galloping,
+}
+
+/// Above this ratio between the longer and the shorter input, galloping beats a plain merge.
+const GALLOPING_RATIO: usize = 16;
+
+/// Picks the cheapest way to intersect two slices of the given lengths.
+///
+/// # Arguments
+/// * `first_length` - The length of the first slice.
+/// * `second_length` - The length of the second slice.
+/// * `sorted` - Whether both slices are sorted in ascending order.
+///
+/// # Returns
+/// * `Strategy` - Hashing for unsorted input, galloping when one sorted slice is much longer
+///   than the other, and a merge walk otherwise.
+fn choose_strategy(first_length: usize, second_length: usize, sorted: bool) -> Strategy {
+    let (shorter, longer) = (
+        first_length.min(second_length),
+        first_length.max(second_length),
+    );
+    if !sorted {
+        Strategy::Hash
+    } else if longer / shorter.max(1) >= GALLOPING_RATIO {
+        Strategy::Galloping
+    } else {
+        Strategy::SortedMerge
+    }
+}
+
+/// Returns the common items of two slices, choosing the strategy from their lengths and
+/// whether they are already sorted. Checking the order costs O(n + m), the same as one
+/// merge walk, so it never dominates.
+///
+/// # Returns
+/// * `(Vec<T>, Strategy)` - The common items as a multiset, and the strategy that found them.
+///   The order of the items depends on the strategy.
+fn common_items_auto<T: Ord + Hash + Clone>(first: &[T], second: &[T]) -> (Vec<T>, Strategy) {
+    let sorted = first.is_sorted() && second.is_sorted();
+    let strategy = choose_strategy(first.len(), second.len(), sorted);
+    let (shorter, longer) = if first.len() <= second.len() {
+        (first, second)
+    } else {
+        (second, first)
+    };
+    let common = match strategy {
+        Strategy::Hash => common_items(shorter.iter().cloned(), longer.iter().cloned()),
+        Strategy::SortedMerge => common_items_sorted(first, second),
+        Strategy::Galloping => common_items_galloping(shorter, longer),
+    };
+    (common, strategy)
+}
+
 fn main() {
     let array1 = ["a", "b", "c", "x"];
     let array2 = ["z", "y", "a"];
@@ -91,4 +307,42 @@ fn main() {
         "Contains common item 3: {}",
         contains_common_item3(&array1, &array2)
     );
+
+    println!(
+        "Common items: {:?}",
+        common_items(array1, array2.iter().copied())
+    );
+    println!(
+        "Common letters: {:?}",
+        common_items("bookkeeper".chars(), "keepsake".chars())
+    );
+    println!(
+        "Sorted merge: {:?}",
+        common_items_sorted(&[1, 2, 2, 3, 5, 8], &[2, 2, 2, 5, 7, 8])
+    );
+
+    let evens: Vec<u32> = (0..100_000).step_by(2).collect();
+    println!(
+        "Galloping: {:?}",
+        common_items_galloping(&[3, 4, 50_000, 99_998], &evens)
+    );
+    println!(
+        "In all three: {:?}",
+        common_items_n_way([
+            vec!["a", "b", "b", "c"],
+            vec!["b", "c", "b", "d"],
+            vec!["c", "b", "b"]
+        ])
+    );
+
+    let squares: Vec<u32> = (0..300).map(|i| i * i).collect();
+    let shuffled = [49, 7, 9, 100, 8];
+    for (first, second) in [
+        (&squares[..], &evens[..]),
+        (&squares[..], &squares[..100]),
+        (&shuffled[..], &squares[..]),
+    ] {
+        let (common, strategy) = common_items_auto(first, second);
+        println!("{:?} found {} common items", strategy, common.len());
+    }
 }